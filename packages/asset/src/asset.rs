@@ -1,8 +1,10 @@
 use crate::querier::{query_balance, query_token_balance, query_token_symbol};
 use apollo_proto_rust::cosmos::base::v1beta1::Coin as ProtoCoin;
 use cosmwasm_std::{
-    Addr, Api, Coin, CustomQuery, MessageInfo, QuerierWrapper, StdError, StdResult, Uint128,
+    to_binary, Addr, Api, BankMsg, Coin, CosmosMsg, CustomQuery, MessageInfo, QuerierWrapper,
+    StdError, StdResult, Uint128, WasmMsg,
 };
+use cw20::Cw20ExecuteMsg;
 use cw_storage_plus::Map;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -127,6 +129,32 @@ impl Asset {
         }
     }
 
+    /// ## Description
+    /// Returns a message that transfers the asset to `recipient`.
+    /// ## Params
+    /// * **self** is the type of the caller object.
+    ///
+    /// * **recipient** is the object of type [`Addr`]
+    pub fn into_msg<T>(self, recipient: &Addr) -> StdResult<CosmosMsg<T>> {
+        match self.info {
+            AssetInfo::Token { contract_addr } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: contract_addr.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: recipient.to_string(),
+                    amount: self.amount,
+                })?,
+                funds: vec![],
+            })),
+            AssetInfo::NativeToken { denom } => Ok(CosmosMsg::Bank(BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: vec![Coin {
+                    denom,
+                    amount: self.amount,
+                }],
+            })),
+        }
+    }
+
     /// ## Description
     /// Implements Assets Into() trait
     /// ## Params
@@ -0,0 +1,225 @@
+use std::fmt;
+
+use cosmwasm_std::{
+    to_binary, Addr, Api, Binary, Deps, DepsMut, Empty, Event, MessageInfo, Order, Response,
+    StdResult, Storage,
+};
+use cw_storage_plus::{Bound, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ContractError;
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+pub const ROLE_GRANTED_EVENT: &str = "apollo/role_granted";
+pub const ROLE_REVOKED_EVENT: &str = "apollo/role_revoked";
+
+/// Members of each role, keyed by `(role, address)`.
+pub const ROLES: Map<(&str, &Addr), Empty> = Map::new("access_control_roles");
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// Grants and revokes every role, including its own
+    Owner,
+    /// Pauses contracts
+    Guardian,
+    /// Runs periodic maintenance such as compounding
+    Keeper,
+    /// Feeds prices to the oracle
+    Feeder,
+    /// Updates fee rates and recipients
+    FeeManager,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Owner => "owner",
+            Role::Guardian => "guardian",
+            Role::Keeper => "keeper",
+            Role::Feeder => "feeder",
+            Role::FeeManager => "fee_manager",
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Messages every contract using roles embeds in its execute message.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[schemars(deny_unknown_fields)]
+pub enum AccessControlExecuteMsg {
+    GrantRole { role: Role, address: String },
+    RevokeRole { role: Role, address: String },
+    RenounceRole { role: Role },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[schemars(deny_unknown_fields)]
+pub enum AccessControlQueryMsg {
+    HasRole {
+        role: Role,
+        address: String,
+    },
+    RoleMembers {
+        role: Role,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct HasRoleResponse {
+    pub has_role: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct RoleMembersResponse {
+    pub role: Role,
+    pub members: Vec<Addr>,
+}
+
+pub fn has_role(storage: &dyn Storage, role: Role, address: &Addr) -> bool {
+    ROLES.has(storage, (role.as_str(), address))
+}
+
+/// Fails with `Unauthorized` unless `sender` holds `role`.
+pub fn assert_role(storage: &dyn Storage, role: Role, sender: &Addr) -> Result<(), ContractError> {
+    if !has_role(storage, role, sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(())
+}
+
+/// Fails with `Unauthorized` unless `sender` holds at least one of `roles`.
+pub fn assert_any_role(
+    storage: &dyn Storage,
+    roles: &[Role],
+    sender: &Addr,
+) -> Result<(), ContractError> {
+    if !roles.iter().any(|role| has_role(storage, *role, sender)) {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(())
+}
+
+/// Grants `role` without any authorization check, for instantiation and migrations.
+pub fn grant_role(storage: &mut dyn Storage, role: Role, address: &Addr) -> StdResult<()> {
+    ROLES.save(storage, (role.as_str(), address), &Empty {})
+}
+
+/// Revokes `role` without any authorization check. The last owner cannot be removed so the
+/// contract never ends up without anyone able to manage roles.
+pub fn revoke_role(
+    storage: &mut dyn Storage,
+    role: Role,
+    address: &Addr,
+) -> Result<(), ContractError> {
+    if !has_role(storage, role, address) {
+        return Err(ContractError::RoleNotGranted {
+            role: role.to_string(),
+            address: address.to_string(),
+        });
+    }
+    if role == Role::Owner && role_members(storage, role, None, Some(2))?.len() == 1 {
+        return Err(ContractError::LastOwner {});
+    }
+    ROLES.remove(storage, (role.as_str(), address));
+    Ok(())
+}
+
+pub fn role_members(
+    storage: &dyn Storage,
+    role: Role,
+    start_after: Option<&Addr>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Addr>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    ROLES
+        .prefix(role.as_str())
+        .keys(storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect()
+}
+
+/// Handles [`AccessControlExecuteMsg`]: owners grant and revoke, anyone can renounce a role
+/// they hold.
+pub fn execute_access_control(
+    deps: DepsMut,
+    info: MessageInfo,
+    msg: AccessControlExecuteMsg,
+) -> Result<Response, ContractError> {
+    let (event, role, address) = match msg {
+        AccessControlExecuteMsg::GrantRole { role, address } => {
+            assert_role(deps.storage, Role::Owner, &info.sender)?;
+            let address = deps.api.addr_validate(&address)?;
+            grant_role(deps.storage, role, &address)?;
+            (ROLE_GRANTED_EVENT, role, address)
+        }
+        AccessControlExecuteMsg::RevokeRole { role, address } => {
+            assert_role(deps.storage, Role::Owner, &info.sender)?;
+            let address = deps.api.addr_validate(&address)?;
+            revoke_role(deps.storage, role, &address)?;
+            (ROLE_REVOKED_EVENT, role, address)
+        }
+        AccessControlExecuteMsg::RenounceRole { role } => {
+            revoke_role(deps.storage, role, &info.sender)?;
+            (ROLE_REVOKED_EVENT, role, info.sender.clone())
+        }
+    };
+
+    Ok(Response::new().add_event(
+        Event::new(event)
+            .add_attribute("role", role.to_string())
+            .add_attribute("address", address)
+            .add_attribute("sender", info.sender),
+    ))
+}
+
+pub fn query_access_control(deps: Deps, msg: AccessControlQueryMsg) -> StdResult<Binary> {
+    match msg {
+        AccessControlQueryMsg::HasRole { role, address } => {
+            let address = deps.api.addr_validate(&address)?;
+            to_binary(&HasRoleResponse {
+                has_role: has_role(deps.storage, role, &address),
+            })
+        }
+        AccessControlQueryMsg::RoleMembers {
+            role,
+            start_after,
+            limit,
+        } => {
+            let start_after = start_after
+                .map(|addr| deps.api.addr_validate(&addr))
+                .transpose()?;
+            to_binary(&RoleMembersResponse {
+                role,
+                members: role_members(deps.storage, role, start_after.as_ref(), limit)?,
+            })
+        }
+    }
+}
+
+/// Validates and grants `role` to every address in `addresses`, for instantiation.
+pub fn init_role(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    role: Role,
+    addresses: &[String],
+) -> StdResult<()> {
+    for address in addresses {
+        grant_role(storage, role, &api.addr_validate(address)?)?;
+    }
+    Ok(())
+}
@@ -1,19 +1,36 @@
-use apollo_asset::asset::Asset;
-use cosmwasm_std::{Addr, Decimal};
+use std::convert::TryFrom;
+
+use apollo_asset::asset::{Asset, AssetInfo};
+use cosmwasm_std::{
+    Addr, Api, CosmosMsg, Decimal, QuerierWrapper, StdError, StdResult, Storage, Uint128,
+};
+use cw_storage_plus::Item;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::{
+    dex_adaptor::swap_msgs,
+    error::ContractError,
+    oracle::query_oracle_price,
+    ownership::OwnershipExecuteMsg,
+    querier::{query_apollo_contracts, query_apollo_dex_adaptor_by_id},
+    slippage::oracle_minimum_receive,
+};
+
+pub const CONFIG: Item<Config> = Item::new("config");
+// TODO - remove after all collectors are migrated
+pub const LEGACY_CONFIG: Item<LegacyConfig> = Item::new("config");
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 #[schemars(deny_unknown_fields)]
 pub struct InstantiateMsg {
     pub owner: String,
     pub distribution_contract: String, //Warchest contract
-    pub terraswap_factory: String,
-    pub base_denom: String,
-    pub aust_token: String,
-    pub target_assets: Vec<Asset>,
+    pub apollo_factory: String,
+    pub dex_adaptor_id: u64,
+    pub base_denom: AssetInfo,
+    pub target_assets: Vec<TargetAsset>,
     pub max_spread: Decimal,
-    pub anchor_market: String, //Anchor MoneyMarket Market contract
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -23,15 +40,14 @@ pub enum ExecuteMsg {
     Deposit {},
     Distribute {},
     UpdateConfig {
-        owner: Option<String>,
         distribution_contract: Option<String>,
-        terraswap_factory: Option<String>,
-        base_denom: Option<String>,
-        target_assets: Option<Vec<Asset>>,
-        aust_token: Option<String>,
+        apollo_factory: Option<String>,
+        dex_adaptor_id: Option<u64>,
+        base_denom: Option<AssetInfo>,
+        target_assets: Option<Vec<TargetAsset>>,
         max_spread: Option<Decimal>,
-        anchor_market: Option<String>,
     },
+    Ownership(OwnershipExecuteMsg), // the only way to change the owner
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -39,6 +55,7 @@ pub enum ExecuteMsg {
 #[schemars(deny_unknown_fields)]
 pub enum QueryMsg {
     Config {},
+    OwnershipProposal {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -46,17 +63,258 @@ pub enum QueryMsg {
 pub struct ConfigResponse {
     pub owner: Addr,
     pub distribution_contract: Addr,
-    pub terraswap_factory: Addr,
-    pub base_denom: String,
-    pub target_assets: Vec<Asset>,
-    pub aust_token: Addr,
+    pub apollo_factory: Addr,
+    pub dex_adaptor_id: u64,
+    pub base_denom: AssetInfo,
+    pub target_assets: Vec<TargetAsset>,
     pub max_spread: Decimal,
     pub total_weight: u64,
-    pub anchor_market: Addr,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 #[schemars(deny_unknown_fields)]
 pub struct MigrateMsg {
+    pub apollo_factory: String,
+    pub dex_adaptor_id: u64,
+    pub target_assets: Option<Vec<TargetAsset>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct TargetAsset {
+    pub info: AssetInfo,
+    pub weight: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct Config {
+    pub owner: Addr,
+    pub distribution_contract: Addr,
+    pub apollo_factory: Addr,
+    pub dex_adaptor_id: u64,
+    pub base_denom: AssetInfo,
+    pub target_assets: Vec<TargetAsset>,
+    pub max_spread: Decimal,
+}
+
+// Terra-era config, target asset weights were stored in `Asset::amount`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct LegacyConfig {
+    pub owner: Addr,
+    pub distribution_contract: Addr,
+    pub terraswap_factory: Addr,
+    pub base_denom: String,
+    pub aust_token: Addr,
     pub target_assets: Vec<Asset>,
+    pub max_spread: Decimal,
+    pub anchor_market: Addr,
+}
+
+impl Config {
+    pub fn validate(&self) -> Result<(), ContractError> {
+        if self.max_spread >= Decimal::one() {
+            return Err(ContractError::InvalidMaxSpread {});
+        }
+        if self.target_assets.is_empty() || self.target_assets.iter().any(|t| t.weight == 0) {
+            return Err(ContractError::InvalidTargetAssets {});
+        }
+        for (i, target) in self.target_assets.iter().enumerate() {
+            if self.target_assets[..i]
+                .iter()
+                .any(|other| other.info.equal(&target.info))
+            {
+                return Err(ContractError::InvalidTargetAssets {});
+            }
+        }
+        self.total_weight()?;
+        Ok(())
+    }
+
+    pub fn total_weight(&self) -> StdResult<u64> {
+        self.target_assets.iter().try_fold(0u64, |acc, t| {
+            acc.checked_add(t.weight)
+                .ok_or_else(|| StdError::generic_err("total target asset weight overflow"))
+        })
+    }
+
+    pub fn is_target(&self, info: &AssetInfo) -> bool {
+        self.target_assets.iter().any(|t| t.info.equal(info))
+    }
+
+    /// Address of the dex adaptor registered with the factory under `dex_adaptor_id`
+    pub fn dex_adaptor(&self, querier: &QuerierWrapper) -> StdResult<Addr> {
+        query_apollo_dex_adaptor_by_id(querier, &self.apollo_factory, self.dex_adaptor_id)
+            .map(|dex| dex.address)
+            .ok_or_else(|| {
+                StdError::generic_err(format!(
+                    "dex adaptor not whitelisted with factory - {}",
+                    self.dex_adaptor_id
+                ))
+            })
+    }
+
+    /// Oracle registered with the factory, prices the collector swaps
+    pub fn oracle(&self, querier: &QuerierWrapper) -> StdResult<Addr> {
+        Ok(query_apollo_contracts(querier, &self.apollo_factory)?.oracle)
+    }
+
+    pub fn to_response(&self) -> StdResult<ConfigResponse> {
+        Ok(ConfigResponse {
+            owner: self.owner.clone(),
+            distribution_contract: self.distribution_contract.clone(),
+            apollo_factory: self.apollo_factory.clone(),
+            dex_adaptor_id: self.dex_adaptor_id,
+            base_denom: self.base_denom.clone(),
+            target_assets: self.target_assets.clone(),
+            max_spread: self.max_spread,
+            total_weight: self.total_weight()?,
+        })
+    }
+}
+
+/// A single swap the collector must perform through `dex_adaptor`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct SwapStep {
+    pub dex_adaptor: Addr,
+    pub offer: Asset,
+    pub ask: AssetInfo,
+    pub max_spread: Decimal,
+}
+
+impl SwapStep {
+    /// Bounds the swap by `max_spread` below the `oracle` rate, prices quoted in `base_denom`.
+    /// A dex simulation would run against the pool being traded and follow a sandwich.
+    pub fn into_msgs(
+        self,
+        querier: &QuerierWrapper,
+        oracle: &Addr,
+        base_denom: &AssetInfo,
+    ) -> Result<Vec<CosmosMsg>, ContractError> {
+        let price = |info: &AssetInfo| {
+            query_oracle_price(
+                querier,
+                oracle.clone(),
+                base_denom.to_string(),
+                info.to_string(),
+                None,
+            )
+        };
+        let minimum_receive = oracle_minimum_receive(
+            &self.offer,
+            price(&self.offer.info)?,
+            price(&self.ask)?,
+            self.max_spread,
+        )?;
+        Ok(swap_msgs(
+            &self.dex_adaptor,
+            None,
+            self.offer,
+            self.ask,
+            Some(minimum_receive),
+            None,
+        )?)
+    }
+}
+
+/// Swaps every collected asset that is neither the base denom nor a target asset into the base
+/// denom. Target assets are distributed as they are, swapping them through the base denom
+/// would pay fees and spread twice.
+pub fn plan_swaps_to_base(
+    config: &Config,
+    balances: &[Asset],
+    dex_adaptor: &Addr,
+) -> Vec<SwapStep> {
+    balances
+        .iter()
+        .filter(|asset| {
+            !asset.amount.is_zero()
+                && !asset.info.equal(&config.base_denom)
+                && !config.is_target(&asset.info)
+        })
+        .map(|asset| SwapStep {
+            dex_adaptor: dex_adaptor.clone(),
+            offer: asset.clone(),
+            ask: config.base_denom.clone(),
+            max_spread: config.max_spread,
+        })
+        .collect()
+}
+
+/// Splits `base_amount` across the target assets by weight and swaps each share that is not
+/// already the base denom. Rounding dust stays in the base denom until the next deposit.
+pub fn plan_swaps_to_targets(
+    config: &Config,
+    base_amount: Uint128,
+    dex_adaptor: &Addr,
+) -> StdResult<Vec<SwapStep>> {
+    let total_weight = config.total_weight()?;
+    if total_weight == 0 {
+        return Ok(vec![]);
+    }
+
+    Ok(config
+        .target_assets
+        .iter()
+        .filter(|target| !target.info.equal(&config.base_denom))
+        .map(|target| SwapStep {
+            dex_adaptor: dex_adaptor.clone(),
+            offer: Asset {
+                info: config.base_denom.clone(),
+                amount: base_amount.multiply_ratio(target.weight, total_weight),
+            },
+            ask: target.info.clone(),
+            max_spread: config.max_spread,
+        })
+        .filter(|step| !step.offer.amount.is_zero())
+        .collect())
+}
+
+/// Target asset balances to send to the distribution contract.
+pub fn plan_distribution(config: &Config, balances: &[Asset]) -> Vec<Asset> {
+    balances
+        .iter()
+        .filter(|asset| !asset.amount.is_zero() && config.is_target(&asset.info))
+        .cloned()
+        .collect()
+}
+
+/// Converts the Terra-era config stored under the same key into the chain agnostic one.
+pub fn migrate_legacy_config(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    msg: MigrateMsg,
+) -> Result<Config, ContractError> {
+    let legacy = LEGACY_CONFIG.load(storage)?;
+
+    let target_assets = match msg.target_assets {
+        Some(target_assets) => target_assets,
+        None => legacy
+            .target_assets
+            .into_iter()
+            .map(|asset| {
+                Ok(TargetAsset {
+                    info: asset.info,
+                    weight: u64::try_from(asset.amount.u128())
+                        .map_err(|_| ContractError::InvalidTargetAssets {})?,
+                })
+            })
+            .collect::<Result<Vec<TargetAsset>, ContractError>>()?,
+    };
+
+    let config = Config {
+        owner: legacy.owner,
+        distribution_contract: legacy.distribution_contract,
+        apollo_factory: api.addr_validate(&msg.apollo_factory)?,
+        dex_adaptor_id: msg.dex_adaptor_id,
+        base_denom: AssetInfo::NativeToken {
+            denom: legacy.base_denom,
+        },
+        target_assets,
+        max_spread: legacy.max_spread,
+    };
+    config.validate()?;
+    CONFIG.save(storage, &config)?;
+
+    Ok(config)
 }
@@ -0,0 +1,285 @@
+use std::collections::BTreeMap;
+
+use apollo_asset::asset::{Asset, AssetInfo};
+use cosmwasm_std::{to_binary, Addr, Coin, CosmosMsg, QuerierWrapper, StdResult, Uint128, WasmMsg};
+use cw20::Cw20ExecuteMsg;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::zap::ZapPool;
+
+/// One hop of a routed swap.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct SwapOperation {
+    pub pool: String,
+    pub offer: AssetInfo,
+    pub ask: AssetInfo,
+}
+
+/// Interface every dex adaptor whitelisted in the factory implements. Pools are identified by
+/// the string the underlying dex uses (pair address, pool id). Cw20 assets are pulled by the
+/// adaptor from an allowance, native assets are attached as funds.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[schemars(deny_unknown_fields)]
+pub enum DexAdaptorExecuteMsg {
+    Swap {
+        pool: Option<String>, // defaults to the adaptor's pool for the pair
+        offer: Asset,
+        ask: AssetInfo,
+        minimum_receive: Option<Uint128>,
+        recipient: Option<String>, // defaults to sender
+    },
+    RouteSwap {
+        operations: Vec<SwapOperation>,
+        offer: Asset,
+        minimum_receive: Option<Uint128>,
+        recipient: Option<String>,
+    },
+    ProvideLiquidity {
+        pool: String,
+        assets: Vec<Asset>,
+        min_lp_out: Option<Uint128>,
+        recipient: Option<String>,
+    },
+    WithdrawLiquidity {
+        pool: String,
+        lp_token: Asset,
+        min_out: Vec<Asset>,
+        recipient: Option<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[schemars(deny_unknown_fields)]
+pub enum DexAdaptorQueryMsg {
+    SimulateSwap {
+        pool: Option<String>,
+        offer: Asset,
+        ask: AssetInfo,
+    },
+    SimulateRouteSwap {
+        operations: Vec<SwapOperation>,
+        offer: Asset,
+    },
+    SimulateProvideLiquidity {
+        pool: String,
+        assets: Vec<Asset>,
+    },
+    SimulateWithdrawLiquidity {
+        pool: String,
+        lp_amount: Uint128,
+    },
+    Pool {
+        pool: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct SimulateSwapResponse {
+    pub return_amount: Uint128,
+    pub commission_amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct SimulateProvideLiquidityResponse {
+    pub lp_amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct SimulateWithdrawLiquidityResponse {
+    pub assets: Vec<Asset>,
+}
+
+/// Pool state, directly usable by the zap planner.
+pub type PoolResponse = ZapPool;
+
+/// Messages executing `msg` on `dex_adaptor` with `assets` attached: native assets as funds,
+/// cw20 assets as allowances for the adaptor. Native assets sharing a denom are merged into one
+/// coin, the bank module rejects duplicate denoms.
+pub fn dex_adaptor_msgs(
+    dex_adaptor: &Addr,
+    msg: &DexAdaptorExecuteMsg,
+    assets: &[Asset],
+) -> StdResult<Vec<CosmosMsg>> {
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    let mut funds: BTreeMap<&str, Uint128> = BTreeMap::new();
+    for asset in assets.iter().filter(|a| !a.amount.is_zero()) {
+        match &asset.info {
+            AssetInfo::Token { contract_addr } => msgs.push(
+                WasmMsg::Execute {
+                    contract_addr: contract_addr.to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                        spender: dex_adaptor.to_string(),
+                        amount: asset.amount,
+                        expires: None,
+                    })?,
+                    funds: vec![],
+                }
+                .into(),
+            ),
+            AssetInfo::NativeToken { denom } => {
+                let amount = funds.entry(denom.as_str()).or_default();
+                *amount = amount.checked_add(asset.amount)?;
+            }
+        }
+    }
+    // the bank module requires funds sorted by denom, which the map iteration order gives
+    let funds = funds
+        .into_iter()
+        .map(|(denom, amount)| Coin {
+            denom: denom.to_string(),
+            amount,
+        })
+        .collect();
+
+    msgs.push(
+        WasmMsg::Execute {
+            contract_addr: dex_adaptor.to_string(),
+            msg: to_binary(msg)?,
+            funds,
+        }
+        .into(),
+    );
+    Ok(msgs)
+}
+
+pub fn swap_msgs(
+    dex_adaptor: &Addr,
+    pool: Option<String>,
+    offer: Asset,
+    ask: AssetInfo,
+    minimum_receive: Option<Uint128>,
+    recipient: Option<String>,
+) -> StdResult<Vec<CosmosMsg>> {
+    dex_adaptor_msgs(
+        dex_adaptor,
+        &DexAdaptorExecuteMsg::Swap {
+            pool,
+            offer: offer.clone(),
+            ask,
+            minimum_receive,
+            recipient,
+        },
+        &[offer],
+    )
+}
+
+pub fn route_swap_msgs(
+    dex_adaptor: &Addr,
+    operations: Vec<SwapOperation>,
+    offer: Asset,
+    minimum_receive: Option<Uint128>,
+    recipient: Option<String>,
+) -> StdResult<Vec<CosmosMsg>> {
+    dex_adaptor_msgs(
+        dex_adaptor,
+        &DexAdaptorExecuteMsg::RouteSwap {
+            operations,
+            offer: offer.clone(),
+            minimum_receive,
+            recipient,
+        },
+        &[offer],
+    )
+}
+
+pub fn provide_liquidity_msgs(
+    dex_adaptor: &Addr,
+    pool: String,
+    assets: Vec<Asset>,
+    min_lp_out: Option<Uint128>,
+    recipient: Option<String>,
+) -> StdResult<Vec<CosmosMsg>> {
+    dex_adaptor_msgs(
+        dex_adaptor,
+        &DexAdaptorExecuteMsg::ProvideLiquidity {
+            pool,
+            assets: assets.clone(),
+            min_lp_out,
+            recipient,
+        },
+        &assets,
+    )
+}
+
+pub fn withdraw_liquidity_msgs(
+    dex_adaptor: &Addr,
+    pool: String,
+    lp_token: Asset,
+    min_out: Vec<Asset>,
+    recipient: Option<String>,
+) -> StdResult<Vec<CosmosMsg>> {
+    dex_adaptor_msgs(
+        dex_adaptor,
+        &DexAdaptorExecuteMsg::WithdrawLiquidity {
+            pool,
+            lp_token: lp_token.clone(),
+            min_out,
+            recipient,
+        },
+        &[lp_token],
+    )
+}
+
+pub fn query_simulate_swap(
+    querier: &QuerierWrapper,
+    dex_adaptor: &Addr,
+    pool: Option<String>,
+    offer: Asset,
+    ask: AssetInfo,
+) -> StdResult<SimulateSwapResponse> {
+    querier.query_wasm_smart(
+        dex_adaptor,
+        &DexAdaptorQueryMsg::SimulateSwap { pool, offer, ask },
+    )
+}
+
+pub fn query_simulate_route_swap(
+    querier: &QuerierWrapper,
+    dex_adaptor: &Addr,
+    operations: Vec<SwapOperation>,
+    offer: Asset,
+) -> StdResult<SimulateSwapResponse> {
+    querier.query_wasm_smart(
+        dex_adaptor,
+        &DexAdaptorQueryMsg::SimulateRouteSwap { operations, offer },
+    )
+}
+
+pub fn query_simulate_provide_liquidity(
+    querier: &QuerierWrapper,
+    dex_adaptor: &Addr,
+    pool: String,
+    assets: Vec<Asset>,
+) -> StdResult<SimulateProvideLiquidityResponse> {
+    querier.query_wasm_smart(
+        dex_adaptor,
+        &DexAdaptorQueryMsg::SimulateProvideLiquidity { pool, assets },
+    )
+}
+
+pub fn query_simulate_withdraw_liquidity(
+    querier: &QuerierWrapper,
+    dex_adaptor: &Addr,
+    pool: String,
+    lp_amount: Uint128,
+) -> StdResult<SimulateWithdrawLiquidityResponse> {
+    querier.query_wasm_smart(
+        dex_adaptor,
+        &DexAdaptorQueryMsg::SimulateWithdrawLiquidity { pool, lp_amount },
+    )
+}
+
+pub fn query_pool(
+    querier: &QuerierWrapper,
+    dex_adaptor: &Addr,
+    pool: String,
+) -> StdResult<PoolResponse> {
+    querier.query_wasm_smart(dex_adaptor, &DexAdaptorQueryMsg::Pool { pool })
+}
@@ -0,0 +1,337 @@
+use std::convert::TryFrom;
+
+use cosmwasm_std::{BlockInfo, Decimal, StdResult, Storage, Timestamp, Uint128, Uint64};
+use cw20::Expiration;
+use cw_storage_plus::Item;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{error::ContractError, utils::validate_distribution_schedule};
+
+/// `(start, end, amount)`: `amount` is released linearly over the inclusive range `start..=end`
+pub type DistributionPeriod = (u64, u64, Uint128);
+
+pub const DISTRIBUTION_SCHEDULE: Item<Vec<DistributionPeriod>> = Item::new("distribution_schedule");
+pub const DISTRIBUTION_CHECKPOINT: Item<DistributionCheckpoint> =
+    Item::new("distribution_checkpoint");
+pub const DISTRIBUTION_UNIT: Item<ScheduleUnit> = Item::new("distribution_unit");
+
+/// Whether the bounds of a schedule are block heights or timestamps (in seconds)
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleUnit {
+    Height,
+    Time,
+}
+
+impl ScheduleUnit {
+    /// Current position of `block` in this unit
+    pub fn now(&self, block: &BlockInfo) -> u64 {
+        match self {
+            ScheduleUnit::Height => block.height,
+            ScheduleUnit::Time => block.time.seconds(),
+        }
+    }
+
+    fn of(bound: &Expiration) -> Result<(ScheduleUnit, u64), ContractError> {
+        match bound {
+            Expiration::AtHeight(height) => Ok((ScheduleUnit::Height, *height)),
+            Expiration::AtTime(time) => Ok((ScheduleUnit::Time, time.seconds())),
+            Expiration::Never {} => Err(ContractError::InvalidDistributionScheduleRanges {}),
+        }
+    }
+
+    fn bound(&self, value: u64) -> Expiration {
+        match self {
+            ScheduleUnit::Height => Expiration::AtHeight(value),
+            ScheduleUnit::Time => Expiration::AtTime(Timestamp::from_seconds(value)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct SchedulePeriod {
+    pub start: Expiration,
+    pub end: Expiration,
+    pub amount: Uint128,
+}
+
+/// Distribution schedule whose bounds are block heights or timestamps, but never both
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct Schedule {
+    pub periods: Vec<SchedulePeriod>,
+}
+
+impl Schedule {
+    /// Builds a schedule from raw `(start, end, amount)` tuples in the given unit.
+    pub fn new(unit: ScheduleUnit, periods: &[DistributionPeriod]) -> Self {
+        Schedule {
+            periods: periods
+                .iter()
+                .map(|(start, end, amount)| SchedulePeriod {
+                    start: unit.bound(*start),
+                    end: unit.bound(*end),
+                    amount: *amount,
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the unit shared by every bound, rejecting mixed or `Never` bounds.
+    pub fn unit(&self) -> Result<ScheduleUnit, ContractError> {
+        let mut unit = None;
+        for period in &self.periods {
+            for bound in [&period.start, &period.end] {
+                let (bound_unit, _) = ScheduleUnit::of(bound)?;
+                match unit {
+                    None => unit = Some(bound_unit),
+                    Some(unit) if unit != bound_unit => {
+                        return Err(ContractError::MixedDistributionScheduleUnits {})
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+        unit.ok_or(ContractError::EmptyDistributionSchedule {})
+    }
+
+    /// Validates the schedule and flattens it to `(start, end, amount)` tuples.
+    pub fn to_periods(&self) -> Result<(ScheduleUnit, Vec<DistributionPeriod>), ContractError> {
+        let unit = self.unit()?;
+        let periods = self
+            .periods
+            .iter()
+            .map(|period| {
+                Ok((
+                    ScheduleUnit::of(&period.start)?.1,
+                    ScheduleUnit::of(&period.end)?.1,
+                    period.amount,
+                ))
+            })
+            .collect::<Result<Vec<DistributionPeriod>, ContractError>>()?;
+        validate_distribution_schedule(&periods)?;
+        Ok((unit, periods))
+    }
+
+    /// Converts the schedule to `to`, using `block` as the reference point and
+    /// `avg_block_time` (seconds per block) to translate between heights and timestamps.
+    /// Periods stay contiguous: each converted period starts right after the previous one ends.
+    pub fn convert(
+        &self,
+        to: ScheduleUnit,
+        block: &BlockInfo,
+        avg_block_time: Decimal,
+    ) -> Result<Schedule, ContractError> {
+        let (from, periods) = self.to_periods()?;
+        if from == to {
+            return Ok(self.clone());
+        }
+        if avg_block_time.is_zero() {
+            return Err(ContractError::InvalidAverageBlockTime {});
+        }
+
+        let mut converted: Vec<DistributionPeriod> = Vec::with_capacity(periods.len());
+        for (start, end, amount) in periods {
+            let start = match converted.last() {
+                Some((_, prev_end, _)) => prev_end + 1,
+                None => convert_point(start, to, block, avg_block_time)?,
+            };
+            let end = convert_point(end, to, block, avg_block_time)?;
+            converted.push((start, end, amount));
+        }
+        validate_distribution_schedule(&converted)?;
+
+        Ok(Schedule::new(to, &converted))
+    }
+}
+
+fn convert_point(
+    value: u64,
+    to: ScheduleUnit,
+    block: &BlockInfo,
+    avg_block_time: Decimal,
+) -> Result<u64, ContractError> {
+    let (reference, target_reference) = match to {
+        ScheduleUnit::Time => (block.height, block.time.seconds()),
+        ScheduleUnit::Height => (block.time.seconds(), block.height),
+    };
+    let distance = Uint128::from(value.max(reference) - value.min(reference));
+    let converted = match to {
+        ScheduleUnit::Time => avg_block_time * distance,
+        ScheduleUnit::Height => {
+            distance.multiply_ratio(Decimal::one().atomics(), avg_block_time.atomics())
+        }
+    };
+    let converted = Uint64::try_from(converted)?.u64();
+
+    if value >= reference {
+        target_reference
+            .checked_add(converted)
+            .ok_or(ContractError::InvalidDistributionScheduleRanges {})
+    } else {
+        target_reference
+            .checked_sub(converted)
+            .ok_or(ContractError::InvalidDistributionScheduleRanges {})
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema, Default)]
+#[schemars(deny_unknown_fields)]
+pub struct DistributionCheckpoint {
+    pub last_distributed: u64,
+    pub total_distributed: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct DistributionInfoResponse {
+    pub schedule: Vec<DistributionPeriod>,
+    pub checkpoint: DistributionCheckpoint,
+    pub total_amount: Uint128,
+}
+
+/// Total amount released by the schedule up to and including `at`.
+pub fn released_until(schedule: &[DistributionPeriod], at: u64) -> StdResult<Uint128> {
+    schedule
+        .iter()
+        .filter(|(start, _, _)| *start <= at)
+        .try_fold(
+            Uint128::zero(),
+            |acc, (start, end, amount)| -> StdResult<Uint128> {
+                if at >= *end {
+                    return Ok(acc.checked_add(*amount)?);
+                }
+                let elapsed = at - start + 1;
+                let duration = end - start + 1;
+                Ok(acc.checked_add(amount.multiply_ratio(elapsed, duration))?)
+            },
+        )
+}
+
+/// Amount released after `from` up to and including `to`.
+pub fn released_between(
+    schedule: &[DistributionPeriod],
+    from: u64,
+    to: u64,
+) -> Result<Uint128, ContractError> {
+    if to < from {
+        return Err(ContractError::InvalidDistributionWindow {});
+    }
+    Ok(released_until(schedule, to)?.checked_sub(released_until(schedule, from)?)?)
+}
+
+/// Validates and stores a new schedule. The checkpoint keeps its position and is rebased on
+/// the new schedule, so only what the new schedule releases after the last distribution is
+/// paid. Distribute before replacing a schedule to pay what the old one released since.
+pub fn save_distribution_schedule(
+    storage: &mut dyn Storage,
+    schedule: Vec<DistributionPeriod>,
+) -> Result<(), ContractError> {
+    validate_distribution_schedule(&schedule)?;
+    let checkpoint = match DISTRIBUTION_CHECKPOINT.may_load(storage)? {
+        Some(checkpoint) if checkpoint.last_distributed > 0 => DistributionCheckpoint {
+            total_distributed: released_until(&schedule, checkpoint.last_distributed)?,
+            last_distributed: checkpoint.last_distributed,
+        },
+        _ => DistributionCheckpoint::default(),
+    };
+    DISTRIBUTION_SCHEDULE.save(storage, &schedule)?;
+    DISTRIBUTION_CHECKPOINT.save(storage, &checkpoint)?;
+    Ok(())
+}
+
+/// Appends a period to the stored schedule. The period must start right after the
+/// current last period ends.
+pub fn append_distribution_period(
+    storage: &mut dyn Storage,
+    period: DistributionPeriod,
+) -> Result<Vec<DistributionPeriod>, ContractError> {
+    let mut schedule = DISTRIBUTION_SCHEDULE.may_load(storage)?.unwrap_or_default();
+    schedule.push(period);
+    validate_distribution_schedule(&schedule)?;
+    DISTRIBUTION_SCHEDULE.save(storage, &schedule)?;
+    Ok(schedule)
+}
+
+/// Returns the amount released since the last checkpoint and moves the checkpoint to `now`.
+/// `now` is a block height or a timestamp, matching the units the schedule was written in.
+pub fn distribute(storage: &mut dyn Storage, now: u64) -> Result<Uint128, ContractError> {
+    let schedule = DISTRIBUTION_SCHEDULE.load(storage)?;
+    let mut checkpoint = DISTRIBUTION_CHECKPOINT
+        .may_load(storage)?
+        .unwrap_or_default();
+    if now < checkpoint.last_distributed {
+        return Err(ContractError::InvalidDistributionWindow {});
+    }
+
+    let released = released_until(&schedule, now)?;
+    let amount = released.checked_sub(checkpoint.total_distributed)?;
+
+    checkpoint.last_distributed = now;
+    checkpoint.total_distributed = released;
+    DISTRIBUTION_CHECKPOINT.save(storage, &checkpoint)?;
+
+    Ok(amount)
+}
+
+/// Amount that `distribute` would return at `now`, without moving the checkpoint.
+pub fn pending_distribution(storage: &dyn Storage, now: u64) -> Result<Uint128, ContractError> {
+    let schedule = DISTRIBUTION_SCHEDULE.load(storage)?;
+    let checkpoint = DISTRIBUTION_CHECKPOINT
+        .may_load(storage)?
+        .unwrap_or_default();
+    if now < checkpoint.last_distributed {
+        return Err(ContractError::InvalidDistributionWindow {});
+    }
+    Ok(released_until(&schedule, now)?.checked_sub(checkpoint.total_distributed)?)
+}
+
+/// Validates and stores a unit-tagged schedule, see [`save_distribution_schedule`].
+pub fn save_schedule(storage: &mut dyn Storage, schedule: &Schedule) -> Result<(), ContractError> {
+    let (unit, periods) = schedule.to_periods()?;
+    DISTRIBUTION_UNIT.save(storage, &unit)?;
+    save_distribution_schedule(storage, periods)
+}
+
+/// Appends a unit-tagged period, rejecting periods in a different unit than the stored schedule.
+pub fn append_schedule_period(
+    storage: &mut dyn Storage,
+    period: SchedulePeriod,
+) -> Result<Vec<DistributionPeriod>, ContractError> {
+    let (unit, periods) = Schedule {
+        periods: vec![period],
+    }
+    .to_periods()?;
+    if unit != DISTRIBUTION_UNIT.load(storage)? {
+        return Err(ContractError::MixedDistributionScheduleUnits {});
+    }
+    append_distribution_period(storage, periods[0])
+}
+
+/// Like `distribute`, reading the current height or time from `block` according to
+/// the unit of the stored schedule.
+pub fn distribute_at(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+) -> Result<Uint128, ContractError> {
+    let unit = DISTRIBUTION_UNIT.load(storage)?;
+    distribute(storage, unit.now(block))
+}
+
+pub fn query_distribution_info(storage: &dyn Storage) -> StdResult<DistributionInfoResponse> {
+    let schedule = DISTRIBUTION_SCHEDULE.load(storage)?;
+    let total_amount = schedule
+        .iter()
+        .try_fold(Uint128::zero(), |acc, (_, _, amount)| {
+            acc.checked_add(*amount)
+        })?;
+    Ok(DistributionInfoResponse {
+        schedule,
+        checkpoint: DISTRIBUTION_CHECKPOINT
+            .may_load(storage)?
+            .unwrap_or_default(),
+        total_amount,
+    })
+}
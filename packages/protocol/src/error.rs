@@ -1,5 +1,6 @@
 use cosmwasm_std::{
-    ConversionOverflowError, Decimal256RangeExceeded, DecimalRangeExceeded, StdError,
+    ConversionOverflowError, Decimal, Decimal256RangeExceeded, DecimalRangeExceeded, OverflowError,
+    StdError, Uint128, Uint256,
 };
 use thiserror::Error;
 
@@ -11,12 +12,18 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized {},
 
-    #[error("Corrupted data found. 8 byte expected.")]
+    #[error("{0}")]
+    Cw20(#[from] cw20_base::ContractError),
+
+    #[error("Corrupted data found in storage key")]
     CorruptedData {},
 
     #[error("{0}")]
     ConversionOverflowError(#[from] ConversionOverflowError),
 
+    #[error("{0}")]
+    OverflowError(#[from] OverflowError),
+
     #[error("{0}")]
     DecimalRangeExceeded(#[from] DecimalRangeExceeded),
 
@@ -40,4 +47,146 @@ pub enum ContractError {
 
     #[error("Distribution schedule contains gaps or overlaps")]
     OverlappedDistributionRanges,
+
+    #[error("Distribution window ends before it starts")]
+    InvalidDistributionWindow,
+
+    #[error("Distribution schedule mixes block heights and timestamps")]
+    MixedDistributionScheduleUnits,
+
+    #[error("Average block time must be greater than zero")]
+    InvalidAverageBlockTime,
+
+    #[error("Max spread must be lower than 100%")]
+    InvalidMaxSpread,
+
+    #[error("Target assets must be unique and have a non-zero weight")]
+    InvalidTargetAssets,
+
+    #[error("Fee must be lower than 100%")]
+    InvalidFee,
+
+    #[error("Fee rate {rate} is above the cap of {cap}")]
+    FeeAboveCap { rate: Decimal, cap: Decimal },
+
+    #[error("Management fee accrued since the last checkpoint would take the whole strategy")]
+    ManagementFeeOverflow {},
+
+    #[error("Cannot distribute rewards while no shares are issued")]
+    NoSharesToDistributeRewards {},
+
+    #[error("Rewards cannot be distributed on native shares, their transfers are not tracked")]
+    NativeShareRewards {},
+
+    #[error("Asset is not part of the pair")]
+    AssetNotInPair {},
+
+    #[error("Pair type is not supported")]
+    UnsupportedPairType {},
+
+    #[error("Pool does not have enough liquidity")]
+    InsufficientLiquidity {},
+
+    #[error("Slippage tolerance must be lower than 100%")]
+    InvalidSlippageTolerance {},
+
+    #[error("Swap route hops do not connect")]
+    InvalidSwapRoute {},
+
+    #[error("No swap route found")]
+    NoSwapRoute {},
+
+    #[error("Max oracle deviation must be lower than 100%")]
+    InvalidMaxOracleDeviation {},
+
+    #[error("Swap returns {actual}, below the oracle bound of {minimum}")]
+    OraclePriceDeviation { minimum: Uint256, actual: Uint128 },
+
+    #[error("Cannot {action} while the strategy is {status}")]
+    ActionNotAllowed { action: String, status: String },
+
+    #[error("Cannot move the strategy from {from} to {to}")]
+    InvalidStatusTransition { from: String, to: String },
+
+    #[error("{address} does not have the {role} role")]
+    RoleNotGranted { role: String, address: String },
+
+    #[error("Cannot revoke the last owner")]
+    LastOwner {},
+
+    #[error("No ownership proposal to claim")]
+    NoOwnershipProposal {},
+
+    #[error("Ownership proposal expired")]
+    OwnershipProposalExpired {},
+
+    #[error("Ownership proposal expiry must be between 1 second and 30 days")]
+    InvalidOwnershipProposalExpiry {},
+
+    #[error("Proposed owner is already the owner")]
+    InvalidOwnershipProposal {},
+
+    #[error("Timelock delay and grace period must be positive")]
+    InvalidTimelockConfig {},
+
+    #[error("Timelocked action cannot be queued before {min_eta}")]
+    InvalidTimelockEta { min_eta: u64 },
+
+    #[error("Timelocked action cannot be executed before {eta}")]
+    TimelockNotReady { eta: u64 },
+
+    #[error("Timelocked action expired")]
+    TimelockExpired {},
+
+    #[error("Message must be a json object keyed by its variant")]
+    InvalidPassMessage {},
+
+    #[error("Deposit exceeds the strategy limits, at most {max_deposit} can be deposited")]
+    DepositLimitExceeded { max_deposit: Uint128 },
+
+    #[error("Amount must not be zero")]
+    InvalidZeroAmount {},
+
+    #[error("Token {token} cannot be received with a {hook} hook")]
+    UnexpectedCw20Token { token: String, hook: String },
+
+    #[error("Native denom {denom} is listed more than once")]
+    DuplicateDenom { denom: String },
+
+    #[error("Expected {expected}{denom} to be sent")]
+    MissingFunds { denom: String, expected: Uint128 },
+
+    #[error("Expected {expected}{denom} to be sent, received {received}{denom}")]
+    FundsMismatch {
+        denom: String,
+        expected: Uint128,
+        received: Uint128,
+    },
+
+    #[error("Unexpected {amount}{denom} sent")]
+    UnexpectedFunds { denom: String, amount: Uint128 },
+
+    #[error("{asset} is a cw20 token, it must be sent with a hook")]
+    Cw20HookRequired { asset: String },
+
+    #[error("{asset} cannot be zapped in")]
+    UnsupportedZapInAsset { asset: String },
+
+    #[error("Nothing to claim")]
+    NothingToClaim {},
+
+    #[error("No withdrawal request to unbond or no unbonding slot free")]
+    NoUnbondingToStart {},
+
+    #[error("Batch must list between 1 and 50 distinct strategies with a non-zero gas limit")]
+    InvalidBatchExecution {},
+
+    #[error("Strategy cannot be executed before {next_execution}")]
+    ExecutionCooldown { next_execution: u64 },
+
+    #[error("Passing {variant} to {contract_addr} is not allowed")]
+    PassMessageNotAllowed {
+        contract_addr: String,
+        variant: String,
+    },
 }
@@ -1,27 +1,108 @@
-use cosmwasm_std::{Addr, Binary, Decimal, Order, StdError, StdResult, Storage, Uint128};
+use apollo_asset::{asset::AssetInfo, pair::PairType};
+use cosmwasm_std::{
+    to_binary, Addr, Binary, Decimal, Empty, Event, Order, Reply, ReplyOn, Response, StdError,
+    StdResult, Storage, SubMsg, SubMsgResult, Uint128, WasmMsg,
+};
 use cw20::Cw20ReceiveMsg;
-use cw_storage_plus::{Item, Map};
+use cw_storage_plus::{Bound, Item, Map};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::{
+    access_control::{assert_role, Role},
+    error::ContractError,
+    ownership::OwnershipExecuteMsg,
+    strategy::{msg::ExecuteMsg as StrategyExecuteMsg, reply::NOT_OPTIMAL_TO_EXECUTE},
+    timelock::{TimelockExecuteMsg, TimelockQueryMsg},
+};
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
 pub static APOLLO_CONTRACTS: Item<ApolloContracts> = Item::new("apollo_contracts");
-pub static APOLLO_DEX_ADAPTORS: Map<u8, Addr> = Map::new("apollo_dex_adaptors");
-pub const APOLLO_DEX_COUNT: Item<u8> = Item::new("apollo_dex_count");
+pub static APOLLO_DEX_ADAPTORS: Map<u64, DexAdaptor> = Map::new("apollo_dex_adaptor_registry");
+pub static APOLLO_DEX_ADAPTOR_IDS: Map<&Addr, u64> = Map::new("apollo_dex_adaptor_ids");
+pub const APOLLO_DEX_COUNT: Item<u64> = Item::new("apollo_dex_adaptor_count");
+
+// TODO - remove after all factories are migrated to the u64 registry
+pub static LEGACY_APOLLO_DEX_ADAPTORS: Map<u8, Addr> = Map::new("apollo_dex_adaptors");
+pub const LEGACY_APOLLO_DEX_COUNT: Item<u8> = Item::new("apollo_dex_count");
 
-// TODO: Is this an iterator? If not we should define as increment instead of next
-pub fn next_dex_id(store: &mut dyn Storage) -> StdResult<u8> {
-    let id: u8 = APOLLO_DEX_COUNT.may_load(store)?.unwrap_or_default() + 1;
+/// Reply id of the first strategy of a batch execution, the others follow by batch index.
+pub const REPLY_BATCH_EXECUTE_STRATEGY: u64 = 1 << 32;
+pub const MAX_BATCH_EXECUTE_SIZE: usize = 50;
+/// Gas each strategy of a batch can use when `ExecuteStrategies` sets no `gas_limit`
+pub const DEFAULT_STRATEGY_GAS_LIMIT: u64 = 2_000_000;
+pub const BATCH_EXECUTION: Item<BatchExecution> = Item::new("batch_execution");
+pub const BATCH_EXECUTION_EVENT: &str = "apollo/batch_execution";
+
+pub const PASS_MESSAGE_EVENT: &str = "apollo/pass_message";
+/// Message variants keepers may pass to each contract, keyed by `(contract, variant)`.
+pub const PASS_MESSAGE_ALLOWLIST: Map<(&Addr, &str), Empty> = Map::new("pass_message_allowlist");
+
+/// Increments the dex adaptor counter and returns the new id. Ids are never reused.
+pub fn next_dex_id(store: &mut dyn Storage) -> StdResult<u64> {
+    let id: u64 = APOLLO_DEX_COUNT.may_load(store)?.unwrap_or_default() + 1;
     APOLLO_DEX_COUNT.save(store, &id)?;
     Ok(id)
 }
 
-pub fn add_dex(storage: &mut dyn Storage, dex_addr: &Addr) -> StdResult<u8> {
+pub fn add_dex(
+    storage: &mut dyn Storage,
+    dex_addr: &Addr,
+    name: String,
+    pair_types: Vec<PairType>,
+) -> StdResult<u64> {
+    if name.trim().is_empty() {
+        return Err(StdError::generic_err("dex adaptor name must not be empty"));
+    }
+    if let Some(id) = APOLLO_DEX_ADAPTOR_IDS.may_load(storage, dex_addr)? {
+        return Err(StdError::generic_err(format!(
+            "dex adaptor already whitelisted with factory - {} (id {})",
+            dex_addr, id
+        )));
+    }
+
     let id = next_dex_id(storage)?;
-    APOLLO_DEX_ADAPTORS.save(storage, id, dex_addr)?;
+    APOLLO_DEX_ADAPTORS.save(
+        storage,
+        id,
+        &DexAdaptor {
+            id,
+            name,
+            address: dex_addr.clone(),
+            pair_types,
+        },
+    )?;
+    APOLLO_DEX_ADAPTOR_IDS.save(storage, dex_addr, &id)?;
     Ok(id)
 }
 
-pub fn remove_dex(storage: &mut dyn Storage, dex_id: u8) -> StdResult<()> {
+pub fn update_dex(
+    storage: &mut dyn Storage,
+    dex_id: u64,
+    name: Option<String>,
+    pair_types: Option<Vec<PairType>>,
+) -> StdResult<DexAdaptor> {
+    APOLLO_DEX_ADAPTORS.update(storage, dex_id, |dex| {
+        let mut dex = dex
+            .ok_or_else(|| StdError::generic_err(format!("dex adaptor not found - {}", dex_id)))?;
+        if let Some(name) = name {
+            if name.trim().is_empty() {
+                return Err(StdError::generic_err("dex adaptor name must not be empty"));
+            }
+            dex.name = name;
+        }
+        if let Some(pair_types) = pair_types {
+            dex.pair_types = pair_types;
+        }
+        Ok(dex)
+    })
+}
+
+pub fn remove_dex(storage: &mut dyn Storage, dex_id: u64) -> StdResult<()> {
+    let dex = APOLLO_DEX_ADAPTORS.load(storage, dex_id)?;
+    APOLLO_DEX_ADAPTOR_IDS.remove(storage, &dex.address);
     APOLLO_DEX_ADAPTORS.remove(storage, dex_id);
     Ok(())
 }
@@ -29,17 +110,265 @@ pub fn remove_dex(storage: &mut dyn Storage, dex_id: u8) -> StdResult<()> {
 pub fn get_apollo_dex_adaptor_by_addr(
     storage: &dyn Storage,
     dex_adaptor_addr: &Addr,
-) -> StdResult<Addr> {
-    match APOLLO_DEX_ADAPTORS
-        .range(storage, None, None, Order::Ascending)
-        .find(|p| &p.as_ref().unwrap().1 == dex_adaptor_addr)
-    {
+) -> StdResult<DexAdaptor> {
+    match APOLLO_DEX_ADAPTOR_IDS.may_load(storage, dex_adaptor_addr)? {
         None => Err(StdError::generic_err(format!(
             "dex adaptor not whitelisted with factory - {:?}",
             dex_adaptor_addr
         ))),
-        Some(found) => Ok(found?.1),
+        Some(id) => APOLLO_DEX_ADAPTORS.load(storage, id),
+    }
+}
+
+pub fn get_apollo_dex_adaptors(
+    storage: &dyn Storage,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<DexAdaptor>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    APOLLO_DEX_ADAPTORS
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, dex)| dex))
+        .collect()
+}
+
+/// Moves dex adaptors stored under the legacy `u8` keys into the `u64` registry.
+/// Ids are preserved so strategies referencing an adaptor by id keep working. Legacy
+/// entries get a placeholder name and no pair types until updated with `UpdateDex`.
+pub fn migrate_legacy_dex_adaptors(storage: &mut dyn Storage) -> StdResult<u64> {
+    let legacy = LEGACY_APOLLO_DEX_ADAPTORS
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(u8, Addr)>>>()?;
+
+    for (legacy_id, address) in legacy.iter() {
+        let id = u64::from(*legacy_id);
+        APOLLO_DEX_ADAPTORS.save(
+            storage,
+            id,
+            &DexAdaptor {
+                id,
+                name: format!("dex_adaptor_{}", id),
+                address: address.clone(),
+                pair_types: vec![],
+            },
+        )?;
+        APOLLO_DEX_ADAPTOR_IDS.save(storage, address, &id)?;
+        LEGACY_APOLLO_DEX_ADAPTORS.remove(storage, *legacy_id);
+    }
+
+    // the counter only moves up, running the migration again must not reuse ids
+    let count = APOLLO_DEX_COUNT.may_load(storage)?.unwrap_or_default();
+    let legacy_count = LEGACY_APOLLO_DEX_COUNT
+        .may_load(storage)?
+        .unwrap_or_default();
+    let highest_id = legacy.iter().map(|(id, _)| *id).max().unwrap_or_default();
+    let migrated_count = count.max(u64::from(legacy_count.max(highest_id)));
+    if migrated_count != count {
+        APOLLO_DEX_COUNT.save(storage, &migrated_count)?;
+    }
+    LEGACY_APOLLO_DEX_COUNT.remove(storage);
+
+    Ok(legacy.len() as u64)
+}
+
+/// Executes every strategy in `strategies`, given as `(strategy_id, address)`, in a
+/// submessage replying on error, so a strategy that is not optimal to execute or fails (unless
+/// `stop_on_error`) does not revert the others. `executor` receives the keeper rewards.
+/// Each submessage gets `gas_limit` gas: a strategy running out of it is reverted and counted
+/// as failed like any other error, without a limit it would abort the whole batch.
+pub fn execute_strategies(
+    storage: &mut dyn Storage,
+    executor: &Addr,
+    strategies: &[(u64, Addr)],
+    stop_on_error: bool,
+    gas_limit: u64,
+) -> Result<Response, ContractError> {
+    let strategy_ids: Vec<u64> = strategies.iter().map(|(id, _)| *id).collect();
+    let has_duplicates = strategy_ids
+        .iter()
+        .enumerate()
+        .any(|(i, id)| strategy_ids[..i].contains(id));
+    if strategy_ids.is_empty()
+        || strategy_ids.len() > MAX_BATCH_EXECUTE_SIZE
+        || has_duplicates
+        || gas_limit == 0
+    {
+        return Err(ContractError::InvalidBatchExecution {});
+    }
+
+    let msg = to_binary(&StrategyExecuteMsg::ExecuteStrategy {
+        executor: Some(executor.to_string()),
+        cost: None,
+    })?;
+    let submsgs = strategies
+        .iter()
+        .enumerate()
+        .map(|(i, (_, address))| SubMsg {
+            id: REPLY_BATCH_EXECUTE_STRATEGY + i as u64,
+            msg: WasmMsg::Execute {
+                contract_addr: address.to_string(),
+                msg: msg.clone(),
+                funds: vec![],
+            }
+            .into(),
+            gas_limit: Some(gas_limit),
+            reply_on: ReplyOn::Error,
+        })
+        .collect::<Vec<_>>();
+
+    BATCH_EXECUTION.save(
+        storage,
+        &BatchExecution {
+            ran: strategy_ids.clone(),
+            strategy_ids,
+            stop_on_error,
+            skipped: vec![],
+            failed: vec![],
+        },
+    )?;
+    Ok(Response::new()
+        .add_submessages(submsgs)
+        .add_attribute("action", "execute_strategies"))
+}
+
+/// Handles the reply of a failed batch execution, i.e. `msg.id` from
+/// `REPLY_BATCH_EXECUTE_STRATEGY` on.
+pub fn batch_execute_strategy_reply(
+    storage: &mut dyn Storage,
+    msg: Reply,
+) -> Result<Response, ContractError> {
+    let error = match msg.result {
+        SubMsgResult::Err(error) => error,
+        SubMsgResult::Ok(_) => return Err(ContractError::UnExpected {}),
+    };
+    let mut batch = BATCH_EXECUTION.load(storage)?;
+    let strategy_id = msg
+        .id
+        .checked_sub(REPLY_BATCH_EXECUTE_STRATEGY)
+        .and_then(|i| batch.strategy_ids.get(i as usize).copied())
+        .ok_or(ContractError::UnknownReply {})?;
+
+    let outcome = if error.contains(NOT_OPTIMAL_TO_EXECUTE) {
+        batch.skipped.push(strategy_id);
+        "skipped"
+    } else if batch.stop_on_error {
+        return Err(ContractError::Std(StdError::generic_err(error)));
+    } else {
+        batch.failed.push(strategy_id);
+        "failed"
+    };
+    batch.ran.retain(|id| *id != strategy_id);
+    BATCH_EXECUTION.save(storage, &batch)?;
+
+    Ok(Response::new().add_event(
+        Event::new(BATCH_EXECUTION_EVENT)
+            .add_attribute("strategy_id", strategy_id.to_string())
+            .add_attribute("outcome", outcome)
+            .add_attribute("error", error),
+    ))
+}
+
+pub fn allow_pass_message(
+    storage: &mut dyn Storage,
+    target: &Addr,
+    variant: &str,
+) -> StdResult<()> {
+    PASS_MESSAGE_ALLOWLIST.save(storage, (target, variant), &Empty {})
+}
+
+pub fn disallow_pass_message(storage: &mut dyn Storage, target: &Addr, variant: &str) {
+    PASS_MESSAGE_ALLOWLIST.remove(storage, (target, variant))
+}
+
+pub fn get_pass_message_allowlist(
+    storage: &dyn Storage,
+    start_after: Option<(Addr, String)>,
+    limit: Option<u32>,
+) -> StdResult<Vec<PassMessageAllowance>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .as_ref()
+        .map(|(target, variant)| Bound::exclusive((target, variant.as_str())));
+
+    PASS_MESSAGE_ALLOWLIST
+        .keys(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            item.map(|(contract_addr, variant)| PassMessageAllowance {
+                contract_addr,
+                variant,
+            })
+        })
+        .collect()
+}
+
+/// Top-level key of a json execute message, i.e. the variant name of `{"variant": {..}}`.
+/// Contracts reject messages with more than one top-level key, so the first key is the one
+/// that will be executed.
+pub fn pass_message_variant(msg: &Binary) -> Result<String, ContractError> {
+    let json = std::str::from_utf8(msg.as_slice())
+        .map_err(|_| ContractError::InvalidPassMessage {})?
+        .trim_start();
+    let key = json
+        .strip_prefix('{')
+        .map(str::trim_start)
+        .and_then(|rest| rest.strip_prefix('"'))
+        .and_then(|rest| rest.split_once('"'))
+        .filter(|(variant, rest)| {
+            !variant.is_empty() && !variant.contains('\\') && rest.trim_start().starts_with(':')
+        })
+        .map(|(variant, _)| variant.to_string());
+    key.ok_or(ContractError::InvalidPassMessage {})
+}
+
+/// The factory owner, as stored in its config, can pass any message, keepers only the
+/// allowlisted variants of each target.
+pub fn assert_pass_message_allowed(
+    storage: &dyn Storage,
+    sender: &Addr,
+    owner: &Addr,
+    target: &Addr,
+    msg: &Binary,
+) -> Result<String, ContractError> {
+    let variant = pass_message_variant(msg)?;
+    if sender == owner {
+        return Ok(variant);
     }
+    assert_role(storage, Role::Keeper, sender)?;
+    if !PASS_MESSAGE_ALLOWLIST.has(storage, (target, variant.as_str())) {
+        return Err(ContractError::PassMessageNotAllowed {
+            contract_addr: target.to_string(),
+            variant,
+        });
+    }
+    Ok(variant)
+}
+
+/// Checks `msg` against the allowlist and forwards it to `target`, logging what was passed.
+pub fn pass_message(
+    storage: &dyn Storage,
+    sender: &Addr,
+    owner: &Addr,
+    target: &Addr,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    let variant = assert_pass_message_allowed(storage, sender, owner, target, &msg)?;
+    Ok(Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: target.to_string(),
+            msg: msg.clone(),
+            funds: vec![],
+        })
+        .add_event(
+            Event::new(PASS_MESSAGE_EVENT)
+                .add_attribute("sender", sender)
+                .add_attribute("contract_addr", target)
+                .add_attribute("variant", variant)
+                .add_attribute("msg", msg.to_base64()),
+        ))
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -63,6 +392,11 @@ pub enum ExecuteMsg {
     ExecuteStrategy {
         strategy_id: u64,
     },
+    ExecuteStrategies {
+        strategy_ids: Vec<u64>,
+        stop_on_error: bool, // strategies not optimal to execute are always skipped
+        gas_limit: Option<u64>, // per strategy, defaults to `DEFAULT_STRATEGY_GAS_LIMIT`
+    },
     WithdrawFromStrategy {
         strategy_id: u64,
         amount: Uint128,
@@ -72,9 +406,9 @@ pub enum ExecuteMsg {
         strategy_id: u64,
     },
     UpdateConfig {
-        owner: Option<String>,
         warchest: Option<String>,
     },
+    Ownership(OwnershipExecuteMsg), // the only way to change the owner
     ZapIntoStrategy {
         strategy_id: u64,
     },
@@ -84,14 +418,32 @@ pub enum ExecuteMsg {
     },
     PassMessage {
         contract_addr: String,
-        msg: Binary,
+        msg: Binary, // owners pass anything, keepers only allowlisted variants
+    },
+    AllowPassMessage {
+        contract_addr: String,
+        variant: String,
+    },
+    DisallowPassMessage {
+        contract_addr: String,
+        variant: String,
     },
     AddDex {
         dex_addr: String,
+        name: String,
+        pair_types: Vec<PairType>,
+    },
+    UpdateDex {
+        dex_id: u64,
+        name: Option<String>,
+        pair_types: Option<Vec<PairType>>,
     },
     RemoveDex {
-        dex_id: u8,
+        dex_id: u64,
     },
+    /// `UpdateStrategy`, `RemoveStrategy` and `RemoveDex` must be queued here and are
+    /// rejected unless sent by the factory itself
+    Timelock(TimelockExecuteMsg),
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -119,6 +471,20 @@ pub enum QueryMsg {
         staker: String,
         strategy_id: u64,
     },
+    GetDexAdaptor {
+        dex_id: u64,
+    },
+    GetDexAdaptors {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    GetOwnershipProposal {},
+    GetTimelock(TimelockQueryMsg),
+    GetLastBatchExecution {},
+    GetPassMessageAllowlist {
+        start_after: Option<(String, String)>, // (contract_addr, variant)
+        limit: Option<u32>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -138,7 +504,22 @@ pub struct ApolloContracts {
 #[schemars(deny_unknown_fields)]
 pub struct ApolloContractsResponse {
     pub contracts: ApolloContracts,
-    pub dex_adaptors: Vec<(u8, Addr)>,
+    pub dex_adaptors: Vec<DexAdaptor>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct DexAdaptor {
+    pub id: u64,
+    pub name: String,
+    pub address: Addr,
+    pub pair_types: Vec<PairType>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct DexAdaptorsResponse {
+    pub dex_adaptors: Vec<DexAdaptor>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -155,7 +536,10 @@ pub struct FactoryStrategyInfoResponse {
     pub tvl: Uint128,
     pub performance_fee: Decimal,
     pub total_shares: Uint128,
-    pub strategy_token: Option<Addr>,
+    pub strategy_token: Option<AssetInfo>,
+    pub tvl_cap: Option<Uint128>,
+    pub user_deposit_cap: Option<Uint128>,
+    pub private: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -185,6 +569,31 @@ pub struct GetConfigResponse {
     pub warchest: Addr,
 }
 
+/// Outcome of the last `ExecuteStrategies`. Strategies are in `ran` until their submessage
+/// fails, there are no replies on success.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct BatchExecution {
+    pub strategy_ids: Vec<u64>,
+    pub stop_on_error: bool,
+    pub ran: Vec<u64>,
+    pub skipped: Vec<u64>,
+    pub failed: Vec<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct PassMessageAllowance {
+    pub contract_addr: Addr,
+    pub variant: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct PassMessageAllowlistResponse {
+    pub allowlist: Vec<PassMessageAllowance>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 #[schemars(deny_unknown_fields)]
 pub struct GetTvlResponse {
@@ -0,0 +1,62 @@
+use std::collections::BTreeMap;
+
+use apollo_asset::asset::{Asset, AssetInfo};
+use cosmwasm_std::Coin;
+
+use crate::error::ContractError;
+
+/// Asserts `funds` hold exactly the native assets of `expected`: each denom sent once, with the
+/// expected amount, and nothing else. Unlike `Asset::assert_sent_native_token_balance` extra
+/// coins are rejected, otherwise they would stay in the contract. Cw20 assets in `expected`
+/// are skipped, they are transferred through their own contract.
+pub fn assert_funds_match(funds: &[Coin], expected: &[Asset]) -> Result<(), ContractError> {
+    let mut expected_amounts = BTreeMap::new();
+    for asset in expected {
+        if let AssetInfo::NativeToken { denom } = &asset.info {
+            if expected_amounts
+                .insert(denom.as_str(), asset.amount)
+                .is_some()
+            {
+                return Err(ContractError::DuplicateDenom {
+                    denom: denom.clone(),
+                });
+            }
+        }
+    }
+
+    let mut received = BTreeMap::new();
+    for coin in funds {
+        if received.insert(coin.denom.as_str(), coin.amount).is_some() {
+            return Err(ContractError::DuplicateDenom {
+                denom: coin.denom.clone(),
+            });
+        }
+        match expected_amounts.get(coin.denom.as_str()) {
+            Some(expected) if *expected == coin.amount => {}
+            Some(expected) if !expected.is_zero() => {
+                return Err(ContractError::FundsMismatch {
+                    denom: coin.denom.clone(),
+                    expected: *expected,
+                    received: coin.amount,
+                })
+            }
+            _ => {
+                return Err(ContractError::UnexpectedFunds {
+                    denom: coin.denom.clone(),
+                    amount: coin.amount,
+                })
+            }
+        }
+    }
+
+    match expected_amounts
+        .into_iter()
+        .find(|(denom, amount)| !amount.is_zero() && !received.contains_key(denom))
+    {
+        Some((denom, expected)) => Err(ContractError::MissingFunds {
+            denom: denom.to_string(),
+            expected,
+        }),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,75 @@
+use std::convert::TryInto;
+
+use cosmwasm_std::Addr;
+
+use crate::error::ContractError;
+
+/// Length of the big-endian length prefix cw-storage-plus writes before every
+/// non-terminal component of a composite key.
+const LENGTH_PREFIX_SIZE: usize = 2;
+
+/// Decodes a key stored as a single byte (`Map<u8, _>`).
+pub fn parse_u8_key(data: &[u8]) -> Result<u8, ContractError> {
+    match data.try_into() {
+        Ok(bytes) => Ok(u8::from_be_bytes(bytes)),
+        Err(_) => Err(ContractError::CorruptedData {}),
+    }
+}
+
+/// Decodes a key stored as 8 big-endian bytes (`Map<u64, _>`).
+pub fn parse_u64_key(data: &[u8]) -> Result<u64, ContractError> {
+    match data.try_into() {
+        Ok(bytes) => Ok(u64::from_be_bytes(bytes)),
+        Err(_) => Err(ContractError::CorruptedData {}),
+    }
+}
+
+/// Decodes a key stored as utf8 bytes (`Map<&str, _>` or `Map<String, _>`).
+pub fn parse_string_key(data: &[u8]) -> Result<String, ContractError> {
+    String::from_utf8(data.to_vec()).map_err(|_| ContractError::CorruptedData {})
+}
+
+/// Decodes a key stored as an address (`Map<&Addr, _>`). The address is not validated.
+pub fn parse_addr_key(data: &[u8]) -> Result<Addr, ContractError> {
+    parse_string_key(data).map(Addr::unchecked)
+}
+
+/// Splits a length-prefixed key into its first component and the remaining bytes.
+pub fn split_first_key(data: &[u8]) -> Result<(&[u8], &[u8]), ContractError> {
+    if data.len() < LENGTH_PREFIX_SIZE {
+        return Err(ContractError::CorruptedData {});
+    }
+    let (prefix, rest) = data.split_at(LENGTH_PREFIX_SIZE);
+    let len = u16::from_be_bytes([prefix[0], prefix[1]]) as usize;
+    if rest.len() < len {
+        return Err(ContractError::CorruptedData {});
+    }
+    Ok(rest.split_at(len))
+}
+
+/// Strips the length-prefixed `namespace` from a full storage key, as returned by a raw
+/// range over the contract storage.
+pub fn strip_namespace<'a>(namespace: &[u8], data: &'a [u8]) -> Result<&'a [u8], ContractError> {
+    match split_first_key(data)? {
+        (ns, rest) if ns == namespace => Ok(rest),
+        _ => Err(ContractError::CorruptedData {}),
+    }
+}
+
+/// Decodes a `(u64, u64)` composite key.
+pub fn parse_u64_u64_key(data: &[u8]) -> Result<(u64, u64), ContractError> {
+    let (first, second) = split_first_key(data)?;
+    Ok((parse_u64_key(first)?, parse_u64_key(second)?))
+}
+
+/// Decodes a `(u64, &Addr)` composite key.
+pub fn parse_u64_addr_key(data: &[u8]) -> Result<(u64, Addr), ContractError> {
+    let (first, second) = split_first_key(data)?;
+    Ok((parse_u64_key(first)?, parse_addr_key(second)?))
+}
+
+/// Decodes a `(&Addr, u64)` composite key.
+pub fn parse_addr_u64_key(data: &[u8]) -> Result<(Addr, u64), ContractError> {
+    let (first, second) = split_first_key(data)?;
+    Ok((parse_addr_key(first)?, parse_u64_key(second)?))
+}
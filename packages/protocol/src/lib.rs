@@ -23,26 +23,46 @@
 //!
 //! **See module docs**
 
+/// Role-based access control
+pub mod access_control;
 /// Revenue collector
 pub mod collector;
+/// Dex adaptor interface
+pub mod dex_adaptor;
+/// Linear distribution schedules
+pub mod distribution;
 /// Error Handler
 pub mod error;
 /// Strategies factory
 pub mod factory;
+/// Native funds validation
+pub mod funds;
+/// Storage key codecs
+pub mod key_codec;
 /// to be removed
 pub mod legacy_strategy;
 /// to be removed
 pub mod legacy_vault;
 /// Price oracle
 pub mod oracle;
+/// Two-step ownership transfer
+pub mod ownership;
 /// querier
 pub mod querier;
+/// Slippage protection
+pub mod slippage;
 /// Strategy Trait
 pub mod strategy;
 /// Strategy Token
 pub mod strategy_token;
+/// Multi-hop swap routes
+pub mod swap_route;
+/// Timelocked governance actions
+pub mod timelock;
 /// Utils
 pub mod utils;
+/// Zap-in and zap-out planning
+pub mod zap;
 
 /// Documentation
 pub mod docs;
@@ -6,15 +6,17 @@ use cw_storage_plus::{Item, Map};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::utils::query_supply;
+use crate::{
+    ownership::OwnershipExecuteMsg,
+    timelock::{TimelockExecuteMsg, TimelockQueryMsg},
+    utils::query_supply,
+};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 #[schemars(deny_unknown_fields)]
 pub enum ExecuteMsg {
-    UpdateConfig {
-        owner: Option<String>,
-    },
+    Ownership(OwnershipExecuteMsg), // the only way to change the owner
     RegisterFeeder {
         asset: String,
         feeder: String,
@@ -26,6 +28,9 @@ pub enum ExecuteMsg {
     FeedPrice {
         prices: Vec<(String, Decimal256)>, // (asset, price)
     },
+    /// `RegisterFeeder` and `RemoveFeeder` must be queued here and are rejected unless sent by
+    /// the oracle itself
+    Timelock(TimelockExecuteMsg),
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -51,6 +56,8 @@ pub enum QueryMsg {
         start_after: Option<String>,
         limit: Option<u32>,
     },
+    OwnershipProposal {},
+    Timelock(TimelockQueryMsg),
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -0,0 +1,140 @@
+use cosmwasm_std::{Addr, Api, Env, Response, StdResult, Storage};
+use cw_storage_plus::Item;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ContractError;
+
+pub const MAX_PROPOSAL_EXPIRY: u64 = 30 * 24 * 60 * 60;
+
+pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
+
+/// New owner proposed by the current one, who only becomes owner once they claim it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct OwnershipProposal {
+    pub owner: Addr,
+    pub expires_at: u64, // block time in seconds
+}
+
+/// Ownership messages shared by factory, oracle and collector.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[schemars(deny_unknown_fields)]
+pub enum OwnershipExecuteMsg {
+    ProposeNewOwner {
+        owner: String,
+        expiry: u64, // seconds the proposal can be claimed for
+    },
+    ClaimOwnership {},
+    DropOwnershipProposal {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct OwnershipProposalResponse {
+    pub proposal: Option<OwnershipProposal>,
+}
+
+/// Records `new_owner` as the pending owner, replacing any previous proposal.
+pub fn propose_new_owner(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    env: &Env,
+    sender: &Addr,
+    owner: &Addr,
+    new_owner: &str,
+    expiry: u64,
+) -> Result<Response, ContractError> {
+    if sender != owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if expiry == 0 || expiry > MAX_PROPOSAL_EXPIRY {
+        return Err(ContractError::InvalidOwnershipProposalExpiry {});
+    }
+    let new_owner = api.addr_validate(new_owner)?;
+    if new_owner == *owner {
+        return Err(ContractError::InvalidOwnershipProposal {});
+    }
+
+    let proposal = OwnershipProposal {
+        owner: new_owner,
+        expires_at: env.block.time.seconds() + expiry,
+    };
+    OWNERSHIP_PROPOSAL.save(storage, &proposal)?;
+    Ok(Response::new()
+        .add_attribute("action", "propose_new_owner")
+        .add_attribute("proposed_owner", proposal.owner)
+        .add_attribute("expires_at", proposal.expires_at.to_string()))
+}
+
+pub fn drop_ownership_proposal(
+    storage: &mut dyn Storage,
+    sender: &Addr,
+    owner: &Addr,
+) -> Result<Response, ContractError> {
+    if sender != owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    OWNERSHIP_PROPOSAL.remove(storage);
+    Ok(Response::new().add_attribute("action", "drop_ownership_proposal"))
+}
+
+/// Lets the proposed owner accept ownership before the proposal expires. `save_owner` writes
+/// the new owner to the contract's config.
+pub fn claim_ownership<F>(
+    storage: &mut dyn Storage,
+    env: &Env,
+    sender: &Addr,
+    save_owner: F,
+) -> Result<Response, ContractError>
+where
+    F: FnOnce(&mut dyn Storage, Addr) -> StdResult<()>,
+{
+    let proposal = OWNERSHIP_PROPOSAL
+        .may_load(storage)?
+        .ok_or(ContractError::NoOwnershipProposal {})?;
+    if *sender != proposal.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if env.block.time.seconds() >= proposal.expires_at {
+        return Err(ContractError::OwnershipProposalExpired {});
+    }
+
+    OWNERSHIP_PROPOSAL.remove(storage);
+    save_owner(storage, proposal.owner.clone())?;
+    Ok(Response::new()
+        .add_attribute("action", "claim_ownership")
+        .add_attribute("new_owner", proposal.owner))
+}
+
+/// Entry point for [`OwnershipExecuteMsg`].
+pub fn execute_ownership<F>(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    env: &Env,
+    sender: &Addr,
+    owner: &Addr,
+    msg: OwnershipExecuteMsg,
+    save_owner: F,
+) -> Result<Response, ContractError>
+where
+    F: FnOnce(&mut dyn Storage, Addr) -> StdResult<()>,
+{
+    match msg {
+        OwnershipExecuteMsg::ProposeNewOwner {
+            owner: new_owner,
+            expiry,
+        } => propose_new_owner(storage, api, env, sender, owner, &new_owner, expiry),
+        OwnershipExecuteMsg::ClaimOwnership {} => claim_ownership(storage, env, sender, save_owner),
+        OwnershipExecuteMsg::DropOwnershipProposal {} => {
+            drop_ownership_proposal(storage, sender, owner)
+        }
+    }
+}
+
+pub fn query_ownership_proposal(storage: &dyn Storage) -> StdResult<OwnershipProposalResponse> {
+    Ok(OwnershipProposalResponse {
+        proposal: OWNERSHIP_PROPOSAL.may_load(storage)?,
+    })
+}
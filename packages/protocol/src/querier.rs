@@ -1,3 +1,4 @@
+use apollo_asset::asset::AssetInfo;
 use cosmwasm_std::{
     to_binary, Addr, Binary, CanonicalAddr, Decimal, Empty, QuerierWrapper, QueryRequest, StdError,
     StdResult, Uint128, Uint256, WasmQuery,
@@ -45,7 +46,9 @@ pub fn query_strategy_strategy_info(querier: &QuerierWrapper, strategy: &Addr) -
         })
 }
 
-use crate::factory::{ApolloContracts, APOLLO_CONTRACTS, APOLLO_DEX_ADAPTORS, APOLLO_DEX_COUNT};
+use crate::factory::{
+    ApolloContracts, DexAdaptor, APOLLO_CONTRACTS, APOLLO_DEX_ADAPTORS, APOLLO_DEX_ADAPTOR_IDS,
+};
 use crate::legacy_strategy::msg::{BaseStrategyConfig, StrategyQueryMsg};
 use crate::strategy::state::ConfigResponse;
 use cw20_base::state::TOKEN_INFO;
@@ -57,9 +60,20 @@ pub fn query_cw20_token_info(
     TOKEN_INFO.query(querier, token_addr)
 }
 
+use super::strategy::share_token::LEGACY_STRATEGY_TOKEN;
 use super::strategy::state::STRATEGY_TOKEN;
-pub fn query_strategy_strategy_token(querier: &QuerierWrapper, strategy: Addr) -> StdResult<Addr> {
-    STRATEGY_TOKEN.query(querier, strategy)
+pub fn query_strategy_strategy_token(
+    querier: &QuerierWrapper,
+    strategy: Addr,
+) -> StdResult<AssetInfo> {
+    STRATEGY_TOKEN
+        .query(querier, strategy.clone())
+        .or_else(|_| {
+            // strategies not yet migrated store the cw20 address
+            Ok(AssetInfo::Token {
+                contract_addr: LEGACY_STRATEGY_TOKEN.query(querier, strategy)?,
+            })
+        })
 }
 
 pub fn query_strategy_config(
@@ -118,31 +132,26 @@ pub fn query_apollo_contracts(
 pub fn query_apollo_dex_adaptor_by_id(
     querier: &QuerierWrapper,
     apollo_factory: &Addr,
-    dex_adaptor_id: u8,
-) -> Option<Addr> {
+    dex_adaptor_id: u64,
+) -> Option<DexAdaptor> {
     APOLLO_DEX_ADAPTORS
         .query(querier, apollo_factory.clone(), dex_adaptor_id)
         .ok()?
 }
 
-// TODO - might be cleaner to just pass the dex id instead of the address, and create a query on factory for all available dexes
 pub fn query_apollo_dex_adaptor_by_addr(
     querier: &QuerierWrapper,
     apollo_factory_addr: &Addr,
     dex_adaptor_addr: &Addr,
-) -> StdResult<u8> {
-    let dex_count = APOLLO_DEX_COUNT.query(querier, apollo_factory_addr.clone())?;
-    for i in 1..=dex_count {
-        if let Some(found) = APOLLO_DEX_ADAPTORS.query(querier, apollo_factory_addr.clone(), i)? {
-            if &found == dex_adaptor_addr {
-                return Ok(i);
-            }
-        }
-    }
-    Err(StdError::generic_err(format!(
-        "dex adaptor not whitelisted with factory - {:?}",
-        dex_adaptor_addr
-    )))
+) -> StdResult<u64> {
+    APOLLO_DEX_ADAPTOR_IDS
+        .query(querier, apollo_factory_addr.clone(), dex_adaptor_addr)?
+        .ok_or_else(|| {
+            StdError::generic_err(format!(
+                "dex adaptor not whitelisted with factory - {:?}",
+                dex_adaptor_addr
+            ))
+        })
 }
 
 #[inline]
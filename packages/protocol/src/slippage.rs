@@ -0,0 +1,117 @@
+use std::convert::TryFrom;
+
+use apollo_asset::asset::{Asset, AssetInfo};
+use cosmwasm_std::{Addr, Decimal, Decimal256, QuerierWrapper, StdError, Uint128, Uint256};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{error::ContractError, oracle::query_oracle_price, utils::decimal_to_decimal256};
+
+/// Bounds a swap must stay within: `max_spread` against the dex simulation and
+/// `max_oracle_deviation` between the simulated execution price and the oracle price.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct SlippageConfig {
+    pub max_spread: Decimal,
+    pub max_oracle_deviation: Decimal,
+}
+
+impl SlippageConfig {
+    pub fn validate(&self) -> Result<(), ContractError> {
+        if self.max_spread >= Decimal::one() {
+            return Err(ContractError::InvalidMaxSpread);
+        }
+        if self.max_oracle_deviation >= Decimal::one() {
+            return Err(ContractError::InvalidMaxOracleDeviation {});
+        }
+        Ok(())
+    }
+}
+
+/// Minimum amount to accept for a swap simulated to return `return_amount`.
+pub fn minimum_receive(return_amount: Uint128, max_spread: Decimal) -> Uint128 {
+    return_amount * (Decimal::one() - max_spread)
+}
+
+/// Amount of the ask asset `offer` is worth at the oracle prices, both quoted in the same base.
+fn oracle_return(
+    offer: &Asset,
+    offer_price: Decimal256,
+    ask_price: Decimal256,
+) -> Result<Uint256, ContractError> {
+    if ask_price.is_zero() {
+        return Err(StdError::generic_err("Oracle price of the ask asset is zero").into());
+    }
+    Ok(Uint256::from(offer.amount).multiply_ratio(offer_price.atomics(), ask_price.atomics()))
+}
+
+/// Fails when receiving `return_amount` of the ask asset for `offer` is worse than the oracle
+/// rate by more than `max_deviation`. Prices are both quoted in the same base.
+pub fn check_oracle_deviation(
+    offer: &Asset,
+    return_amount: Uint128,
+    offer_price: Decimal256,
+    ask_price: Decimal256,
+    max_deviation: Decimal,
+) -> Result<(), ContractError> {
+    let minimum = oracle_return(offer, offer_price, ask_price)?
+        * (Decimal256::one() - decimal_to_decimal256(max_deviation)?);
+    if Uint256::from(return_amount) < minimum {
+        return Err(ContractError::OraclePriceDeviation {
+            minimum,
+            actual: return_amount,
+        });
+    }
+    Ok(())
+}
+
+/// Minimum receive for swapping `offer` at the oracle rate less `max_spread`. Unlike a
+/// simulation run in the same transaction, the floor does not move with the pool traded against.
+pub fn oracle_minimum_receive(
+    offer: &Asset,
+    offer_price: Decimal256,
+    ask_price: Decimal256,
+    max_spread: Decimal,
+) -> Result<Uint128, ContractError> {
+    let minimum = oracle_return(offer, offer_price, ask_price)?
+        * (Decimal256::one() - decimal_to_decimal256(max_spread)?);
+    Ok(Uint128::try_from(minimum)?)
+}
+
+/// Minimum receive for swapping `offer` into `ask`, after checking that the simulated
+/// `return_amount` is in line with the oracle. A sandwiched pool moves the simulation but not
+/// the oracle, so the swap fails instead of executing at a manipulated price.
+pub fn checked_minimum_receive(
+    querier: &QuerierWrapper,
+    oracle: &Addr,
+    base: String,
+    offer: &Asset,
+    ask: &AssetInfo,
+    return_amount: Uint128,
+    config: &SlippageConfig,
+    oldest_acceptable_price: Option<u64>,
+) -> Result<Uint128, ContractError> {
+    config.validate()?;
+    let offer_price = query_oracle_price(
+        querier,
+        oracle.clone(),
+        base.clone(),
+        offer.info.to_string(),
+        oldest_acceptable_price,
+    )?;
+    let ask_price = query_oracle_price(
+        querier,
+        oracle.clone(),
+        base,
+        ask.to_string(),
+        oldest_acceptable_price,
+    )?;
+    check_oracle_deviation(
+        offer,
+        return_amount,
+        offer_price,
+        ask_price,
+        config.max_oracle_deviation,
+    )?;
+    Ok(minimum_receive(return_amount, config.max_spread))
+}
@@ -0,0 +1,137 @@
+use std::convert::TryInto;
+
+use cosmwasm_std::{
+    Addr, Decimal256, Empty, QuerierWrapper, StdError, StdResult, Storage, Uint128, Uint256,
+};
+use cw_storage_plus::{Item, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{error::ContractError, oracle::query_oracle_price};
+
+use super::{
+    fee::{convert_deposit, DepositConversion, FEES},
+    state::{BASE_DENOM, BASE_TOKEN, ORACLE},
+};
+
+pub const DEPOSIT_LIMITS: Item<DepositLimits> = Item::new("deposit_limits");
+pub const DEPOSIT_ALLOWLIST: Map<&Addr, Empty> = Map::new("deposit_allowlist");
+/// Base tokens deposited by each user, net of what they redeemed. The user cap is checked
+/// against it rather than the share balance, which a user can empty by transferring shares.
+pub const USER_DEPOSITS: Map<&Addr, Uint128> = Map::new("user_deposits");
+
+/// Guarded rollout of a strategy. No limits are enforced when unset.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct DepositLimits {
+    /// Cap on the strategy TVL, valued in the oracle base denom
+    pub tvl_cap: Option<Uint128>,
+    /// Cap on the base tokens a single user can have deposited, see `USER_DEPOSITS`
+    pub user_cap: Option<Uint128>,
+    /// Only addresses in `DEPOSIT_ALLOWLIST` can deposit
+    pub private: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct MaxDepositResponse {
+    pub max_deposit: Uint128, // in base tokens
+}
+
+/// Base tokens a user can still deposit. `base_price` is the oracle price of the base token,
+/// only used when a TVL cap is set.
+pub fn compute_max_deposit(
+    limits: &DepositLimits,
+    allowlisted: bool,
+    base_price: Decimal256,
+    total_bond_amount: Uint128,
+    user_deposits: Uint128,
+) -> StdResult<Uint128> {
+    if limits.private && !allowlisted {
+        return Ok(Uint128::zero());
+    }
+    let mut max_deposit = Uint128::MAX;
+    if let Some(tvl_cap) = limits.tvl_cap {
+        if base_price.is_zero() {
+            return Err(StdError::generic_err(
+                "Oracle price of the base token is zero",
+            ));
+        }
+        let tvl = Uint256::from(total_bond_amount) * base_price;
+        let remaining: Uint128 = Uint256::from(tvl_cap)
+            .saturating_sub(tvl)
+            .multiply_ratio(Decimal256::one().atomics(), base_price.atomics())
+            .try_into()?;
+        max_deposit = max_deposit.min(remaining);
+    }
+    if let Some(user_cap) = limits.user_cap {
+        max_deposit = max_deposit.min(user_cap.saturating_sub(user_deposits));
+    }
+    Ok(max_deposit)
+}
+
+/// [`compute_max_deposit`] for `user`, with the TVL priced by the strategy oracle.
+pub fn query_max_deposit(
+    querier: &QuerierWrapper,
+    storage: &dyn Storage,
+    user: &Addr,
+    total_bond_amount: Uint128,
+) -> StdResult<MaxDepositResponse> {
+    let limits = DEPOSIT_LIMITS.may_load(storage)?.unwrap_or_default();
+    let base_price = match limits.tvl_cap {
+        Some(_) => query_oracle_price(
+            querier,
+            ORACLE.load(storage)?,
+            BASE_DENOM.load(storage)?.to_string(),
+            BASE_TOKEN.load(storage)?.to_string(),
+            None,
+        )?,
+        None => Decimal256::one(),
+    };
+    Ok(MaxDepositResponse {
+        max_deposit: compute_max_deposit(
+            &limits,
+            DEPOSIT_ALLOWLIST.has(storage, user),
+            base_price,
+            total_bond_amount,
+            USER_DEPOSITS.may_load(storage, user)?.unwrap_or_default(),
+        )?,
+    })
+}
+
+/// Shared deposit path: checks the deposit limits, records the deposit in `USER_DEPOSITS`
+/// then splits `amount` into fee and shares. `total_bond_amount` and `total_shares` are
+/// measured before the deposit.
+pub fn checked_convert_deposit(
+    querier: &QuerierWrapper,
+    storage: &mut dyn Storage,
+    user: &Addr,
+    amount: Uint128,
+    total_bond_amount: Uint128,
+    total_shares: Uint128,
+) -> Result<DepositConversion, ContractError> {
+    let max_deposit = query_max_deposit(querier, storage, user, total_bond_amount)?.max_deposit;
+    if amount > max_deposit {
+        return Err(ContractError::DepositLimitExceeded { max_deposit });
+    }
+    let deposits = USER_DEPOSITS.may_load(storage, user)?.unwrap_or_default();
+    USER_DEPOSITS.save(storage, user, &deposits.checked_add(amount)?)?;
+    let fees = FEES.may_load(storage)?.unwrap_or_default();
+    convert_deposit(&fees, amount, total_bond_amount, total_shares)
+}
+
+/// Frees `amount` base tokens of the `owner` deposits, called by the redeem path with the base
+/// tokens the redeemed shares were worth. Redeeming more than deposited, e.g. shares received
+/// by transfer, only clears the record.
+pub fn release_user_deposit(
+    storage: &mut dyn Storage,
+    owner: &Addr,
+    amount: Uint128,
+) -> StdResult<()> {
+    let deposits = USER_DEPOSITS.may_load(storage, owner)?.unwrap_or_default();
+    match deposits.saturating_sub(amount) {
+        remaining if remaining.is_zero() => USER_DEPOSITS.remove(storage, owner),
+        remaining => USER_DEPOSITS.save(storage, owner, &remaining)?,
+    }
+    Ok(())
+}
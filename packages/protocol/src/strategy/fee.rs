@@ -0,0 +1,392 @@
+use std::convert::TryInto;
+
+use apollo_asset::asset::Asset;
+use cosmwasm_std::{
+    Addr, Decimal, Decimal256, Env, Event, QuerierWrapper, Response, StdResult, Storage, Uint128,
+    Uint256,
+};
+use cw_storage_plus::Item;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::ContractError,
+    utils::{calculate_user_bonds, decimal_to_decimal256},
+};
+
+use super::{share_token::mint_shares, state::BASE_TOKEN};
+
+pub const PERFORMANCE_FEE_EVENT: &str = "apollo/performance_fee";
+pub const MANAGEMENT_FEE_EVENT: &str = "apollo/management_fee";
+pub const DEPOSIT_FEE_EVENT: &str = "apollo/deposit_fee";
+pub const WITHDRAWAL_FEE_EVENT: &str = "apollo/withdrawal_fee";
+
+pub const PERFORMANCE_FEE: Item<PerformanceFeeConfig> = Item::new("performance_fee");
+pub const HIGH_WATER_MARK: Item<Decimal256> = Item::new("high_water_mark");
+pub const FEES: Item<FeeConfig> = Item::new("fees");
+pub const MANAGEMENT_FEE_CHECKPOINT: Item<u64> = Item::new("management_fee_checkpoint");
+
+pub const SECONDS_PER_YEAR: u64 = 31_536_000;
+/// 5% per year
+pub const MAX_MANAGEMENT_FEE: Decimal = Decimal::raw(50_000_000_000_000_000);
+/// 2% of the deposited amount
+pub const MAX_DEPOSIT_FEE: Decimal = Decimal::raw(20_000_000_000_000_000);
+/// 2% of the withdrawn amount
+pub const MAX_WITHDRAWAL_FEE: Decimal = Decimal::raw(20_000_000_000_000_000);
+
+/// How the performance fee is paid to the collector
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeCollection {
+    /// Mint new strategy tokens to the collector, diluting depositors by the fee
+    MintShares,
+    /// Transfer the fee in base tokens, which must be held by the strategy when collected
+    TransferAssets,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct PerformanceFeeConfig {
+    pub performance_fee: Decimal,
+    pub collector: Addr,
+    pub collection: FeeCollection,
+}
+
+/// Result of accruing the performance fee for one compound
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PerformanceFeeAccrual {
+    /// Base tokens earned above the high-water mark
+    pub yield_amount: Uint128,
+    /// Part of `yield_amount` taken as fee
+    pub fee_amount: Uint128,
+    /// Strategy tokens to mint to the collector, zero unless fees are collected as shares
+    pub fee_shares: Uint128,
+    /// Share price after the fee, which becomes the new high-water mark
+    pub high_water_mark: Decimal256,
+}
+
+impl PerformanceFeeConfig {
+    pub fn validate(&self) -> Result<(), ContractError> {
+        if self.performance_fee >= Decimal::one() {
+            return Err(ContractError::InvalidFee {});
+        }
+        Ok(())
+    }
+}
+
+/// Computes the fee owed on the yield above the high-water mark. `total_bond_amount` and
+/// `total_shares` must be measured after compounding and before any fee is taken.
+/// The first call only records the high-water mark.
+pub fn compute_performance_fee(
+    high_water_mark: Option<Decimal256>,
+    performance_fee: Decimal,
+    collection: &FeeCollection,
+    total_bond_amount: Uint128,
+    total_shares: Uint128,
+) -> Result<PerformanceFeeAccrual, ContractError> {
+    let no_fee = |high_water_mark| PerformanceFeeAccrual {
+        yield_amount: Uint128::zero(),
+        fee_amount: Uint128::zero(),
+        fee_shares: Uint128::zero(),
+        high_water_mark,
+    };
+
+    if total_shares.is_zero() {
+        return Ok(no_fee(high_water_mark.unwrap_or_else(Decimal256::one)));
+    }
+    let share_price = Decimal256::from_ratio(total_bond_amount, total_shares);
+    let high_water_mark = match high_water_mark {
+        Some(high_water_mark) if share_price > high_water_mark => high_water_mark,
+        Some(high_water_mark) => return Ok(no_fee(high_water_mark)),
+        None => return Ok(no_fee(share_price)),
+    };
+
+    let total_bond_amount = Uint256::from(total_bond_amount);
+    let total_shares = Uint256::from(total_shares);
+    let yield_amount = total_bond_amount - total_shares * high_water_mark;
+    let fee_amount = yield_amount * decimal_to_decimal256(performance_fee)?;
+
+    let (fee_shares, high_water_mark) = match collection {
+        FeeCollection::MintShares if !fee_amount.is_zero() => {
+            let fee_shares =
+                total_shares.multiply_ratio(fee_amount, total_bond_amount - fee_amount);
+            (
+                fee_shares,
+                Decimal256::from_ratio(total_bond_amount, total_shares + fee_shares),
+            )
+        }
+        FeeCollection::MintShares => (Uint256::zero(), share_price),
+        FeeCollection::TransferAssets => (
+            Uint256::zero(),
+            Decimal256::from_ratio(total_bond_amount - fee_amount, total_shares),
+        ),
+    };
+
+    Ok(PerformanceFeeAccrual {
+        yield_amount: yield_amount.try_into()?,
+        fee_amount: fee_amount.try_into()?,
+        fee_shares: fee_shares.try_into()?,
+        high_water_mark,
+    })
+}
+
+/// Accrues the performance fee and moves the high-water mark.
+pub fn accrue_performance_fee(
+    storage: &mut dyn Storage,
+    total_bond_amount: Uint128,
+    total_shares: Uint128,
+) -> Result<PerformanceFeeAccrual, ContractError> {
+    let config = PERFORMANCE_FEE.load(storage)?;
+    let accrual = compute_performance_fee(
+        HIGH_WATER_MARK.may_load(storage)?,
+        config.performance_fee,
+        &config.collection,
+        total_bond_amount,
+        total_shares,
+    )?;
+    HIGH_WATER_MARK.save(storage, &accrual.high_water_mark)?;
+    Ok(accrual)
+}
+
+/// Builds the messages paying `accrual` to the collector along with the
+/// standardized performance fee event.
+pub fn performance_fee_response(
+    querier: &QuerierWrapper,
+    storage: &mut dyn Storage,
+    env: &Env,
+    accrual: &PerformanceFeeAccrual,
+) -> StdResult<Response> {
+    let config = PERFORMANCE_FEE.load(storage)?;
+
+    let mut response = Response::new();
+    if !accrual.fee_amount.is_zero() {
+        response = match config.collection {
+            FeeCollection::MintShares => response.add_messages(mint_shares(
+                querier,
+                storage,
+                env,
+                &config.collector,
+                accrual.fee_shares,
+            )?),
+            FeeCollection::TransferAssets => response.add_message(
+                Asset {
+                    info: BASE_TOKEN.load(storage)?,
+                    amount: accrual.fee_amount,
+                }
+                .into_msg(&config.collector)?,
+            ),
+        };
+    }
+
+    Ok(response.add_event(
+        Event::new(PERFORMANCE_FEE_EVENT)
+            .add_attribute("collector", config.collector.to_string())
+            .add_attribute("performance_fee", config.performance_fee.to_string())
+            .add_attribute("yield_amount", accrual.yield_amount)
+            .add_attribute("fee_amount", accrual.fee_amount)
+            .add_attribute("fee_shares", accrual.fee_shares)
+            .add_attribute("high_water_mark", accrual.high_water_mark.to_string()),
+    ))
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct Fee {
+    pub rate: Decimal,
+    pub recipient: Addr,
+}
+
+/// Optional fees on top of the performance fee. The management fee rate is annualized.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema, Default)]
+#[schemars(deny_unknown_fields)]
+pub struct FeeConfig {
+    pub management_fee: Option<Fee>,
+    pub deposit_fee: Option<Fee>,
+    pub withdrawal_fee: Option<Fee>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct FeesResponse {
+    pub performance_fee: Option<PerformanceFeeConfig>,
+    pub management_fee: Option<Fee>,
+    pub deposit_fee: Option<Fee>,
+    pub withdrawal_fee: Option<Fee>,
+}
+
+impl FeeConfig {
+    pub fn validate(&self) -> Result<(), ContractError> {
+        let caps = [
+            (&self.management_fee, MAX_MANAGEMENT_FEE),
+            (&self.deposit_fee, MAX_DEPOSIT_FEE),
+            (&self.withdrawal_fee, MAX_WITHDRAWAL_FEE),
+        ];
+        for (fee, cap) in caps {
+            if let Some(fee) = fee {
+                if fee.rate > cap {
+                    return Err(ContractError::FeeAboveCap {
+                        rate: fee.rate,
+                        cap,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn rate(fee: &Option<Fee>) -> Decimal {
+        fee.as_ref().map(|fee| fee.rate).unwrap_or_default()
+    }
+}
+
+/// Validates the fees against the caps before storing them.
+pub fn save_fee_config(storage: &mut dyn Storage, fees: &FeeConfig) -> Result<(), ContractError> {
+    fees.validate()?;
+    FEES.save(storage, fees)?;
+    Ok(())
+}
+
+/// Deposit split into the entry fee and the shares minted for the net amount
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct DepositConversion {
+    pub fee_amount: Uint128,
+    pub net_amount: Uint128,
+    pub shares: Uint128,
+}
+
+/// Withdrawal split into the exit fee and the amount returned to the user
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct WithdrawalConversion {
+    pub gross_amount: Uint128,
+    pub fee_amount: Uint128,
+    pub net_amount: Uint128,
+}
+
+/// Shares minted for depositing `amount`, after the deposit fee. `total_bond_amount` and
+/// `total_shares` are measured before the deposit.
+pub fn convert_deposit(
+    fees: &FeeConfig,
+    amount: Uint128,
+    total_bond_amount: Uint128,
+    total_shares: Uint128,
+) -> Result<DepositConversion, ContractError> {
+    let fee_amount = amount * FeeConfig::rate(&fees.deposit_fee);
+    let net_amount = amount.checked_sub(fee_amount)?;
+    let shares = if total_shares.is_zero() || total_bond_amount.is_zero() {
+        net_amount
+    } else {
+        net_amount.multiply_ratio(total_shares, total_bond_amount)
+    };
+
+    Ok(DepositConversion {
+        fee_amount,
+        net_amount,
+        shares,
+    })
+}
+
+/// Base tokens returned for burning `shares`, after the withdrawal fee.
+pub fn convert_withdrawal(
+    fees: &FeeConfig,
+    shares: Uint128,
+    total_bond_amount: Uint128,
+    total_shares: Uint128,
+) -> Result<WithdrawalConversion, ContractError> {
+    let gross_amount = calculate_user_bonds(shares, total_shares, total_bond_amount)?;
+    let fee_amount = gross_amount * FeeConfig::rate(&fees.withdrawal_fee);
+
+    Ok(WithdrawalConversion {
+        gross_amount,
+        fee_amount,
+        net_amount: gross_amount.checked_sub(fee_amount)?,
+    })
+}
+
+/// Strategy tokens to mint so the recipient owns `rate * elapsed / year` of the strategy.
+/// Fails when that fraction reaches the whole strategy rather than dropping the fee.
+pub fn compute_management_fee_shares(
+    rate: Decimal,
+    elapsed_seconds: u64,
+    total_shares: Uint128,
+) -> Result<Uint128, ContractError> {
+    let fraction =
+        Decimal256::from_ratio(elapsed_seconds, SECONDS_PER_YEAR) * decimal_to_decimal256(rate)?;
+    if fraction.is_zero() {
+        return Ok(Uint128::zero());
+    }
+    if fraction >= Decimal256::one() {
+        return Err(ContractError::ManagementFeeOverflow {});
+    }
+    // minting s shares out of (total + s) gives the recipient `fraction` of the strategy
+    let fee_shares = Uint256::from(total_shares)
+        .multiply_ratio(fraction.atomics(), (Decimal256::one() - fraction).atomics());
+    Ok(fee_shares.try_into()?)
+}
+
+/// Accrues the management fee since the last checkpoint and returns the mint message and
+/// event. Must be called before any change to the share supply.
+pub fn accrue_management_fee(
+    querier: &QuerierWrapper,
+    storage: &mut dyn Storage,
+    env: &Env,
+    total_shares: Uint128,
+) -> Result<Response, ContractError> {
+    let now_seconds = env.block.time.seconds();
+    let last = MANAGEMENT_FEE_CHECKPOINT.may_load(storage)?;
+    MANAGEMENT_FEE_CHECKPOINT.save(storage, &now_seconds)?;
+
+    let (fee, last) = match (
+        FEES.may_load(storage)?.unwrap_or_default().management_fee,
+        last,
+    ) {
+        (Some(fee), Some(last)) if now_seconds > last => (fee, last),
+        _ => return Ok(Response::new()),
+    };
+    let fee_shares = compute_management_fee_shares(fee.rate, now_seconds - last, total_shares)?;
+    if fee_shares.is_zero() {
+        return Ok(Response::new());
+    }
+
+    Ok(Response::new()
+        .add_messages(mint_shares(
+            querier,
+            storage,
+            env,
+            &fee.recipient,
+            fee_shares,
+        )?)
+        .add_event(
+            Event::new(MANAGEMENT_FEE_EVENT)
+                .add_attribute("recipient", fee.recipient.to_string())
+                .add_attribute("management_fee", fee.rate.to_string())
+                .add_attribute("fee_shares", fee_shares),
+        ))
+}
+
+/// Message paying an entry or exit fee in base tokens, with its event.
+pub fn base_token_fee_response(
+    storage: &dyn Storage,
+    fee: &Option<Fee>,
+    fee_amount: Uint128,
+    event_type: &str,
+) -> StdResult<Response> {
+    let fee = match fee {
+        Some(fee) if !fee_amount.is_zero() => fee,
+        _ => return Ok(Response::new()),
+    };
+
+    Ok(Response::new()
+        .add_message(
+            Asset {
+                info: BASE_TOKEN.load(storage)?,
+                amount: fee_amount,
+            }
+            .into_msg(&fee.recipient)?,
+        )
+        .add_event(
+            Event::new(event_type)
+                .add_attribute("recipient", fee.recipient.to_string())
+                .add_attribute("rate", fee.rate.to_string())
+                .add_attribute("fee_amount", fee_amount),
+        ))
+}
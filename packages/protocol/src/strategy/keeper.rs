@@ -0,0 +1,175 @@
+use std::convert::TryInto;
+
+use apollo_asset::asset::Asset;
+use cosmwasm_std::{
+    Addr, Decimal, Decimal256, Env, Event, QuerierWrapper, Response, StdResult, Storage, Uint128,
+    Uint256, Uint64,
+};
+use cw_storage_plus::Item;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{error::ContractError, oracle::query_oracle_price, utils::decimal_to_decimal256};
+
+use super::state::{BASE_DENOM, ORACLE};
+
+pub const KEEPER_REWARD_EVENT: &str = "apollo/keeper_reward";
+
+pub const KEEPER_REWARD_CONFIG: Item<KeeperRewardConfig> = Item::new("keeper_reward_config");
+pub const KEEPER_PAYOUTS: Item<KeeperPayouts> = Item::new("keeper_payouts");
+pub const LAST_EXECUTION: Item<u64> = Item::new("last_execution");
+
+/// 5% of the harvested rewards
+pub const MAX_KEEPER_REWARD_SHARE: Decimal = Decimal::raw(50_000_000_000_000_000);
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct KeeperRewardConfig {
+    /// Share of each harvested reward paid to the executor
+    pub reward_share: Decimal,
+    /// Cap on the value of one payout, in the oracle base denom
+    pub max_reward_value: Uint128,
+    /// Minimum seconds between two executions
+    pub cooldown: u64,
+}
+
+impl KeeperRewardConfig {
+    pub fn validate(&self) -> Result<(), ContractError> {
+        if self.reward_share > MAX_KEEPER_REWARD_SHARE {
+            return Err(ContractError::FeeAboveCap {
+                rate: self.reward_share,
+                cap: MAX_KEEPER_REWARD_SHARE,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Everything paid to keepers by the strategy.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct KeeperPayouts {
+    pub executions: u64,
+    pub total_value: Uint128, // in the oracle base denom at payout time
+    pub assets: Vec<Asset>,
+}
+
+/// Keeper reward for one execution, to deduct from the harvested rewards before compounding.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct KeeperReward {
+    pub assets: Vec<Asset>,
+    pub value: Uint128,
+}
+
+/// Fails when the strategy was executed less than `cooldown` seconds ago, then records this
+/// execution. Without it a keeper could execute every block to farm dust rewards.
+pub fn check_execution_cooldown(storage: &mut dyn Storage, env: &Env) -> Result<(), ContractError> {
+    let now = env.block.time.seconds();
+    let cooldown = KEEPER_REWARD_CONFIG
+        .may_load(storage)?
+        .map(|config| config.cooldown)
+        .unwrap_or_default();
+    if let Some(last_execution) = LAST_EXECUTION.may_load(storage)? {
+        let next_execution = Uint64::new(last_execution)
+            .checked_add(Uint64::new(cooldown))?
+            .u64();
+        if now < next_execution {
+            return Err(ContractError::ExecutionCooldown { next_execution });
+        }
+    }
+    LAST_EXECUTION.save(storage, &now)?;
+    Ok(())
+}
+
+/// `reward_share` of each harvested asset, scaled down together so their total value stays
+/// under `max_reward_value`. `harvested` pairs each asset with its oracle price.
+pub fn compute_keeper_reward(
+    config: &KeeperRewardConfig,
+    harvested: &[(Asset, Decimal256)],
+) -> Result<KeeperReward, ContractError> {
+    let share = decimal_to_decimal256(config.reward_share)?;
+    let mut assets = vec![];
+    let mut value = Uint256::zero();
+    for (asset, price) in harvested {
+        let amount = asset.amount * config.reward_share;
+        value += Uint256::from(asset.amount) * share * *price;
+        assets.push(asset.info.clone().to_asset(amount));
+    }
+
+    let cap = Uint256::from(config.max_reward_value);
+    if value > cap {
+        for asset in assets.iter_mut() {
+            asset.amount = Uint256::from(asset.amount)
+                .multiply_ratio(cap, value)
+                .try_into()?;
+        }
+        value = cap;
+    }
+
+    Ok(KeeperReward {
+        assets: assets.into_iter().filter(|a| !a.amount.is_zero()).collect(),
+        value: value.try_into()?,
+    })
+}
+
+/// [`compute_keeper_reward`] priced by the strategy oracle.
+pub fn query_keeper_reward(
+    querier: &QuerierWrapper,
+    storage: &dyn Storage,
+    harvested: &[Asset],
+    oldest_acceptable_price: Option<u64>,
+) -> Result<KeeperReward, ContractError> {
+    let config = match KEEPER_REWARD_CONFIG.may_load(storage)? {
+        Some(config) => config,
+        None => {
+            return Ok(KeeperReward {
+                assets: vec![],
+                value: Uint128::zero(),
+            })
+        }
+    };
+    let oracle = ORACLE.load(storage)?;
+    let base = BASE_DENOM.load(storage)?.to_string();
+    let priced = harvested
+        .iter()
+        .map(|asset| {
+            let price = query_oracle_price(
+                querier,
+                oracle.clone(),
+                base.clone(),
+                asset.info.to_string(),
+                oldest_acceptable_price,
+            )?;
+            Ok((asset.clone(), price))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    compute_keeper_reward(&config, &priced)
+}
+
+/// Pays `reward` to `executor` and adds it to the strategy's keeper payouts.
+pub fn pay_keeper_reward(
+    storage: &mut dyn Storage,
+    executor: &Addr,
+    reward: KeeperReward,
+) -> Result<Response, ContractError> {
+    let mut payouts = KEEPER_PAYOUTS.may_load(storage)?.unwrap_or_default();
+    payouts.executions += 1;
+    payouts.total_value = payouts.total_value.checked_add(reward.value)?;
+    for asset in &reward.assets {
+        match payouts.assets.iter_mut().find(|a| a.info == asset.info) {
+            Some(total) => total.amount = total.amount.checked_add(asset.amount)?,
+            None => payouts.assets.push(asset.clone()),
+        }
+    }
+    KEEPER_PAYOUTS.save(storage, &payouts)?;
+
+    let mut event = Event::new(KEEPER_REWARD_EVENT)
+        .add_attribute("executor", executor)
+        .add_attribute("value", reward.value);
+    let mut response = Response::new();
+    for asset in reward.assets {
+        event = event.add_attribute("reward", asset.to_string());
+        response = response.add_message(asset.into_msg(executor)?);
+    }
+    Ok(response.add_event(event))
+}
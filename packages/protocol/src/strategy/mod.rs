@@ -1,8 +1,16 @@
 pub mod contract;
 pub mod convex;
+pub mod deposit_limit;
+pub mod fee;
+pub mod keeper;
 pub mod msg;
 pub mod querier;
+pub mod receive;
 pub mod reply;
+pub mod reward;
+pub mod share_token;
 pub mod state;
+pub mod status;
+pub mod unbonding;
 #[cfg(test)]
 mod utests;
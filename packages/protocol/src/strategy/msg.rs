@@ -1,5 +1,10 @@
+use crate::strategy::reward::RewardIndex;
+use crate::strategy::share_token::ShareTokenInit;
+use crate::strategy::status::StrategyStatus;
+use crate::strategy_token::StrategyTokenHookMsg;
 use apollo_asset::asset::{Asset, AssetInfo};
-use cosmwasm_std::{Addr, Decimal, Decimal256, Uint128};
+use cosmwasm_std::{Addr, Decimal256, Uint128};
+use cw20::Cw20ReceiveMsg;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -22,6 +27,19 @@ pub enum BaseStrategyQueryMsg<S> {
     },
     Tvl {},
     Apr {},
+    Fees {},
+    ClaimableRewards {
+        address: String,
+    },
+    Status {},
+    KeeperPayouts {},
+    Claims {
+        address: String,
+    },
+    MaxDeposit {
+        address: String,
+    },
+    DepositLimits {},
     Strategy(S),
 }
 
@@ -30,7 +48,7 @@ pub enum BaseStrategyQueryMsg<S> {
 pub struct StrategyInfo {
     pub total_bond_amount: Uint128,
     pub total_shares: Uint128,
-    pub global_index: Decimal, // TODO - remove this after migration, legacy
+    pub reward_indexes: Vec<RewardIndex>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -39,7 +57,7 @@ pub struct StrategyInfo {
 pub struct UserInfo {
     pub base_token_balance: Uint128,
     pub shares: Uint128,
-    pub index: Decimal, // TODO - remove this after migration, legacy
+    pub claimable_rewards: Vec<Asset>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -83,12 +101,67 @@ pub type ExecuteMsg = BaseStrategyExecuteMsg<()>;
 #[schemars(deny_unknown_fields)]
 pub enum BaseStrategyExecuteMsg<S> {
     ExecuteStrategy {
-        executor: Option<String>, // recipient for execution fee (defaults to sender)
+        executor: Option<String>, // recipient for the keeper reward (defaults to sender)
         cost: Option<Uint128>, // provide cost amount to query shouldExecute() with before executing
     },
+    StrategyTokenHook(StrategyTokenHookMsg), // sent by the strategy token on share transfers
+    Receive(Cw20ReceiveMsg), // base tokens, shares or zap assets, see `StrategyCw20HookMsg`
+    Deposit {
+        amount: Uint128,           // of the native base token, must match the funds sent
+        recipient: Option<String>, // receives the shares, defaults to sender
+    },
+    ZapIn {
+        offer: Asset,              // native asset in `ZAP_IN_ASSETS`, must match the funds sent
+        recipient: Option<String>, // receives the shares, defaults to sender
+        min_shares: Option<Uint128>,
+    },
+    Claim {
+        recipient: Option<String>, // defaults to sender
+    },
+    UpdateStatus {
+        status: StrategyStatus, // owner or guardian, see `StrategyStatus::can_transition_to`
+    },
+    UpdateGuardian {
+        guardian: Option<String>,
+    },
+    // token-factory shares sent as funds, cw20 shares are redeemed through `Receive`
+    Redeem {
+        recipient: Option<String>, // defaults to sender
+    },
+    EmergencyRedeem {
+        recipient: Option<String>, // defaults to sender
+    },
+    StartUnbonding {}, // unbonds the pending withdrawal requests once a slot is free
+    ClaimUnbonded {
+        recipient: Option<String>, // defaults to sender
+    },
     Strategy(S),
 }
 
+/// Hooks of cw20 tokens sent to a strategy with `Send`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[schemars(deny_unknown_fields)]
+pub enum StrategyCw20HookMsg {
+    /// Base tokens to deposit
+    Deposit {
+        recipient: Option<String>, // receives the shares, defaults to sender
+    },
+    /// Shares to withdraw
+    Redeem {
+        recipient: Option<String>, // defaults to sender
+    },
+    /// Shares to redeem pro-rata once the strategy is in emergency
+    EmergencyRedeem {
+        recipient: Option<String>, // defaults to sender
+    },
+    /// Token in `ZAP_IN_ASSETS` to zap into the base token and deposit
+    ZapIn {
+        recipient: Option<String>, // receives the shares, defaults to sender
+        min_shares: Option<Uint128>,
+    },
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 #[schemars(deny_unknown_fields)]
 pub struct InstantiateMsg {
@@ -96,9 +169,7 @@ pub struct InstantiateMsg {
     pub apollo_factory: String, //The Apollo Factory contract
     pub asset_token: AssetInfo, //Apollo token
     pub oracle_contract: String,
-    pub strategy_token_code_id: u64,
-    pub strategy_token_symbol: String,
-    pub strategy_token_name: String,
+    pub share_token: ShareTokenInit,
     pub adaptor_addr: String,
     pub strategy_id: u64,
 }
@@ -1,15 +1,17 @@
 use cosmwasm_std::{Addr, Deps, Env, StdResult, Uint128};
 
 use super::{
+    fee::{FeesResponse, FEES, PERFORMANCE_FEE},
+    keeper::{KeeperPayouts, KEEPER_PAYOUTS},
     msg::{AprResponse, ShouldExecuteResponse, StrategyInfo, TvlResponse, UserInfo},
+    reward::{claimable_rewards, ClaimableRewardsResponse, REWARD_INDEXES},
+    share_token::{query_share_balance, query_share_supply},
     state::{BASE_TOKEN, ORACLE, STAKING_ADAPTOR, STRATEGY_TOKEN},
+    status::{load_status, StatusResponse, EMERGENCY_UNWIND, GUARDIAN},
 };
 
-use crate::utils::{calculate_user_bonds, query_token_balance};
-use crate::{
-    querier::query_cw20_token_info,
-    strategy::state::{BaseConfig, BASE_DENOM, FACTORY},
-};
+use crate::strategy::state::{BaseConfig, BASE_DENOM, FACTORY};
+use crate::utils::calculate_user_bonds;
 
 pub fn query_total_bond_amount(
     _deps: Deps,
@@ -33,16 +35,15 @@ pub fn query_total_bond_amount(
 //Query the total shares in the Strategy and total base_token amount in the Strategy
 pub fn query_strategy_info(deps: Deps, env: Env, token: Option<Addr>) -> StdResult<StrategyInfo> {
     let _adaptor_addr = STAKING_ADAPTOR.load(deps.storage)?;
-    let strategy_token = STRATEGY_TOKEN.load(deps.storage)?;
 
     let total_bond_amount = query_total_bond_amount(deps, &env, token)?;
 
-    let total_shares = query_cw20_token_info(&deps.querier, strategy_token)?.total_supply;
+    let total_shares = query_share_supply(deps)?;
 
     Ok(StrategyInfo {
         total_bond_amount,
         total_shares,
-        global_index: Default::default(),
+        reward_indexes: REWARD_INDEXES.may_load(deps.storage)?.unwrap_or_default(),
     })
 }
 
@@ -52,11 +53,9 @@ pub fn query_user_info(
     user_addr: String,
     token: Option<Addr>,
 ) -> StdResult<UserInfo> {
-    let strategy_token = STRATEGY_TOKEN.load(deps.storage)?;
     let user_addr = deps.api.addr_validate(&user_addr)?;
 
-    // TODO: change to raw query
-    let user_shares = query_token_balance(&deps.querier, strategy_token, user_addr)?;
+    let user_shares = query_share_balance(&deps.querier, deps.storage, user_addr.clone())?;
 
     let strategy_info = query_strategy_info(deps, env, token)?;
 
@@ -69,7 +68,7 @@ pub fn query_user_info(
     Ok(UserInfo {
         base_token_balance: user_bonds,
         shares: user_shares,
-        index: Default::default(),
+        claimable_rewards: claimable_rewards(deps.storage, &user_addr, user_shares)?,
     })
 }
 
@@ -128,6 +127,36 @@ pub fn query_should_execute(_deps: Deps, _cost: Uint128) -> StdResult<ShouldExec
     })
 }
 
+pub fn query_fees(deps: Deps) -> StdResult<FeesResponse> {
+    let fees = FEES.may_load(deps.storage)?.unwrap_or_default();
+    Ok(FeesResponse {
+        performance_fee: PERFORMANCE_FEE.may_load(deps.storage)?,
+        management_fee: fees.management_fee,
+        deposit_fee: fees.deposit_fee,
+        withdrawal_fee: fees.withdrawal_fee,
+    })
+}
+
+pub fn query_keeper_payouts(deps: Deps) -> StdResult<KeeperPayouts> {
+    Ok(KEEPER_PAYOUTS.may_load(deps.storage)?.unwrap_or_default())
+}
+
+pub fn query_status(deps: Deps) -> StdResult<StatusResponse> {
+    Ok(StatusResponse {
+        status: load_status(deps.storage)?,
+        guardian: GUARDIAN.may_load(deps.storage)?,
+        emergency_unwind: EMERGENCY_UNWIND.may_load(deps.storage)?,
+    })
+}
+
+pub fn query_claimable_rewards(deps: Deps, address: String) -> StdResult<ClaimableRewardsResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let shares = query_share_balance(&deps.querier, deps.storage, address.clone())?;
+    Ok(ClaimableRewardsResponse {
+        rewards: claimable_rewards(deps.storage, &address, shares)?,
+    })
+}
+
 // TODO - remove after migration
 pub fn query_base_config(deps: Deps) -> StdResult<BaseConfig> {
     Ok(BaseConfig {
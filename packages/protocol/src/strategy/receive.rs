@@ -0,0 +1,229 @@
+use apollo_asset::asset::{Asset, AssetInfo};
+use cosmwasm_std::{from_binary, Addr, Api, MessageInfo, StdResult, Storage, Uint128};
+use cw20::Cw20ReceiveMsg;
+use cw_storage_plus::Item;
+
+use crate::{error::ContractError, funds::assert_funds_match};
+
+use super::{
+    msg::StrategyCw20HookMsg,
+    state::{BASE_TOKEN, STRATEGY_TOKEN},
+    status::StrategyAction,
+};
+
+/// Assets other than the base token accepted by `ZapIn`, native or cw20.
+pub const ZAP_IN_ASSETS: Item<Vec<AssetInfo>> = Item::new("zap_in_assets");
+
+/// What the strategy must do with the tokens it received, once their origin is checked.
+/// Shared by the cw20 hooks and the native `Deposit` and `ZapIn` messages.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReceiveAction {
+    Deposit {
+        recipient: Addr,
+        amount: Uint128,
+    },
+    Redeem {
+        owner: Addr,
+        recipient: Addr,
+        shares: Uint128,
+    },
+    EmergencyRedeem {
+        owner: Addr,
+        recipient: Addr,
+        shares: Uint128,
+    },
+    ZapIn {
+        recipient: Addr,
+        offer: Asset,
+        min_shares: Option<Uint128>,
+    },
+}
+
+impl StrategyCw20HookMsg {
+    pub fn action(&self) -> StrategyAction {
+        match self {
+            StrategyCw20HookMsg::Deposit { .. } | StrategyCw20HookMsg::ZapIn { .. } => {
+                StrategyAction::Deposit
+            }
+            StrategyCw20HookMsg::Redeem { .. } => StrategyAction::Withdraw,
+            StrategyCw20HookMsg::EmergencyRedeem { .. } => StrategyAction::EmergencyRedeem,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            StrategyCw20HookMsg::Deposit { .. } => "deposit",
+            StrategyCw20HookMsg::Redeem { .. } => "redeem",
+            StrategyCw20HookMsg::EmergencyRedeem { .. } => "emergency_redeem",
+            StrategyCw20HookMsg::ZapIn { .. } => "zap_in",
+        }
+    }
+}
+
+fn is_cw20(info: &AssetInfo, token: &Addr) -> bool {
+    matches!(info, AssetInfo::Token { contract_addr } if contract_addr == token)
+}
+
+/// Unified `Receive` handler: decodes the hook and checks that `info.sender`, the cw20
+/// contract, is the token the hook expects. Base tokens can only be deposited, shares only
+/// redeemed and zap assets only zapped in.
+pub fn parse_cw20_hook(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    info: &MessageInfo,
+    msg: Cw20ReceiveMsg,
+) -> Result<ReceiveAction, ContractError> {
+    let token = &info.sender;
+    let sender = api.addr_validate(&msg.sender)?;
+    let hook: StrategyCw20HookMsg = from_binary(&msg.msg)?;
+    let recipient = |recipient: &Option<String>| -> StdResult<Addr> {
+        recipient
+            .as_ref()
+            .map_or(Ok(sender.clone()), |r| api.addr_validate(r))
+    };
+
+    let expected = match &hook {
+        StrategyCw20HookMsg::Deposit { .. } => is_cw20(&BASE_TOKEN.load(storage)?, token),
+        StrategyCw20HookMsg::Redeem { .. } | StrategyCw20HookMsg::EmergencyRedeem { .. } => {
+            is_cw20(&STRATEGY_TOKEN.load(storage)?, token)
+        }
+        StrategyCw20HookMsg::ZapIn { .. } => ZAP_IN_ASSETS
+            .may_load(storage)?
+            .unwrap_or_default()
+            .iter()
+            .any(|asset| is_cw20(asset, token)),
+    };
+    if !expected {
+        return Err(ContractError::UnexpectedCw20Token {
+            token: token.to_string(),
+            hook: hook.name().to_string(),
+        });
+    }
+
+    Ok(match &hook {
+        StrategyCw20HookMsg::Deposit { recipient: r } => ReceiveAction::Deposit {
+            recipient: recipient(r)?,
+            amount: msg.amount,
+        },
+        StrategyCw20HookMsg::Redeem { recipient: r } => ReceiveAction::Redeem {
+            recipient: recipient(r)?,
+            owner: sender.clone(),
+            shares: msg.amount,
+        },
+        StrategyCw20HookMsg::EmergencyRedeem { recipient: r } => ReceiveAction::EmergencyRedeem {
+            recipient: recipient(r)?,
+            owner: sender.clone(),
+            shares: msg.amount,
+        },
+        StrategyCw20HookMsg::ZapIn {
+            recipient: r,
+            min_shares,
+        } => ReceiveAction::ZapIn {
+            recipient: recipient(r)?,
+            offer: AssetInfo::Token {
+                contract_addr: token.clone(),
+            }
+            .to_asset(msg.amount),
+            min_shares: *min_shares,
+        },
+    })
+}
+
+fn recipient_or_sender(
+    api: &dyn Api,
+    info: &MessageInfo,
+    recipient: Option<String>,
+) -> StdResult<Addr> {
+    recipient.map_or(Ok(info.sender.clone()), |r| api.addr_validate(&r))
+}
+
+/// Native `Deposit`: the base token must be native and `info.funds` exactly `amount` of it.
+pub fn parse_native_deposit(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    info: &MessageInfo,
+    amount: Uint128,
+    recipient: Option<String>,
+) -> Result<ReceiveAction, ContractError> {
+    let base_token = BASE_TOKEN.load(storage)?;
+    if !base_token.is_native_token() {
+        return Err(ContractError::Cw20HookRequired {
+            asset: base_token.to_string(),
+        });
+    }
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+    assert_funds_match(&info.funds, &[base_token.to_asset(amount)])?;
+    Ok(ReceiveAction::Deposit {
+        recipient: recipient_or_sender(api, info, recipient)?,
+        amount,
+    })
+}
+
+/// Native `Redeem`: the shares must be token-factory shares and `info.funds` only hold them.
+pub fn parse_native_redeem(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    info: &MessageInfo,
+    recipient: Option<String>,
+) -> Result<ReceiveAction, ContractError> {
+    let share_token = STRATEGY_TOKEN.load(storage)?;
+    let shares = match &share_token {
+        AssetInfo::NativeToken { denom } => info
+            .funds
+            .iter()
+            .find(|coin| coin.denom == *denom)
+            .map_or(Uint128::zero(), |coin| coin.amount),
+        AssetInfo::Token { .. } => {
+            return Err(ContractError::Cw20HookRequired {
+                asset: share_token.to_string(),
+            })
+        }
+    };
+    if shares.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+    assert_funds_match(&info.funds, &[share_token.to_asset(shares)])?;
+    Ok(ReceiveAction::Redeem {
+        owner: info.sender.clone(),
+        recipient: recipient_or_sender(api, info, recipient)?,
+        shares,
+    })
+}
+
+/// Native `ZapIn`: `offer` must be a native asset in `ZAP_IN_ASSETS` and `info.funds` exactly
+/// `offer`.
+pub fn parse_native_zap_in(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    info: &MessageInfo,
+    offer: Asset,
+    recipient: Option<String>,
+    min_shares: Option<Uint128>,
+) -> Result<ReceiveAction, ContractError> {
+    if !offer.is_native_token() {
+        return Err(ContractError::Cw20HookRequired {
+            asset: offer.info.to_string(),
+        });
+    }
+    let supported = ZAP_IN_ASSETS
+        .may_load(storage)?
+        .unwrap_or_default()
+        .iter()
+        .any(|asset| asset.equal(&offer.info));
+    if !supported {
+        return Err(ContractError::UnsupportedZapInAsset {
+            asset: offer.info.to_string(),
+        });
+    }
+    if offer.amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+    assert_funds_match(&info.funds, &[offer.clone()])?;
+    Ok(ReceiveAction::ZapIn {
+        recipient: recipient_or_sender(api, info, recipient)?,
+        offer,
+        min_shares,
+    })
+}
@@ -3,34 +3,36 @@ use cosmwasm_std::{
     SubMsgResult,
 };
 
+use apollo_asset::asset::AssetInfo;
+
 use crate::{error::ContractError, utils::parse_contract_addr_from_instantiate_event};
 
-use super::state::STRATEGY_TOKEN;
+use super::{state::STRATEGY_TOKEN, status::record_emergency_unwind};
 
 /**
  * Submessage signals
  */
 pub const REPLY_SAVE_STRATEGY_TOKEN_ADDR: u64 = 0;
 pub const REPLY_STRATEGY_EXECUTE_GRACE_FAIL: u64 = 1;
+pub const REPLY_EMERGENCY_UNWIND: u64 = 2;
+
+/// Error strategies return when compounding now would cost more than it earns.
+pub const NOT_OPTIMAL_TO_EXECUTE: &str = "not optimal to execute";
 
 /**
  * Base reply handler for strategy. Sets up strategy token and proxy addresses.
  */
-pub fn base_strategy_reply(
-    deps: DepsMut,
-    _env: Env,
-    msg: Reply,
-) -> Result<Response, ContractError> {
+pub fn base_strategy_reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
     match msg.id {
         REPLY_SAVE_STRATEGY_TOKEN_ADDR => match msg.result {
-            SubMsgResult::Ok(subcall) => {
-                reply_save_addr(deps, subcall, |s, addr| STRATEGY_TOKEN.save(s, &addr))
-            }
+            SubMsgResult::Ok(subcall) => reply_save_addr(deps, subcall, |s, contract_addr| {
+                STRATEGY_TOKEN.save(s, &AssetInfo::Token { contract_addr })
+            }),
             SubMsgResult::Err(_) => Err(ContractError::FailedToInitializeStrategyToken {}),
         },
         REPLY_STRATEGY_EXECUTE_GRACE_FAIL => match msg.result {
             SubMsgResult::Err(e) => {
-                if e.contains("not optimal to execute") {
+                if e.contains(NOT_OPTIMAL_TO_EXECUTE) {
                     Ok(Response::new().add_attribute("autocompound", e))
                 } else {
                     Err(ContractError::Std(StdError::generic_err(e)))
@@ -38,6 +40,7 @@ pub fn base_strategy_reply(
             }
             SubMsgResult::Ok(_) => Err(ContractError::UnExpected {}),
         },
+        REPLY_EMERGENCY_UNWIND => record_emergency_unwind(deps, &env),
         _ => Err(ContractError::UnknownReply {}),
     }
 }
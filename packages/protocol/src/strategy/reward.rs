@@ -0,0 +1,193 @@
+use std::convert::TryInto;
+
+use apollo_asset::asset::{Asset, AssetInfo};
+use cosmwasm_std::{Addr, Decimal256, Event, Response, StdResult, Storage, Uint128, Uint256};
+use cw_storage_plus::{Item, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{error::ContractError, strategy_token::StrategyTokenHookMsg};
+
+use super::state::STRATEGY_TOKEN;
+
+pub const CLAIM_REWARDS_EVENT: &str = "apollo/claim_rewards";
+
+pub const REWARD_INDEXES: Item<Vec<RewardIndex>> = Item::new("reward_indexes");
+pub const USER_REWARDS: Map<&Addr, Vec<UserReward>> = Map::new("user_rewards");
+
+/// Cumulative amount of a reward token distributed per share.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct RewardIndex {
+    pub info: AssetInfo,
+    pub index: Decimal256,
+}
+
+/// Reward index of a user at their last share change, and the rewards accrued up to it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct UserReward {
+    pub info: AssetInfo,
+    pub index: Decimal256,
+    pub pending: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ClaimableRewardsResponse {
+    pub rewards: Vec<Asset>,
+}
+
+/// Adds `reward` to the index of its token, split over `total_shares`. Not available for
+/// token-factory shares: bank transfers skip the checkpoints, so the index could be claimed
+/// again from every address the shares move to.
+pub fn distribute_rewards(
+    storage: &mut dyn Storage,
+    reward: &Asset,
+    total_shares: Uint128,
+) -> Result<(), ContractError> {
+    if let Some(AssetInfo::NativeToken { .. }) = STRATEGY_TOKEN.may_load(storage)? {
+        return Err(ContractError::NativeShareRewards {});
+    }
+    if reward.amount.is_zero() {
+        return Ok(());
+    }
+    if total_shares.is_zero() {
+        return Err(ContractError::NoSharesToDistributeRewards {});
+    }
+
+    let increment = Decimal256::from_ratio(reward.amount, total_shares);
+    let mut indexes = REWARD_INDEXES.may_load(storage)?.unwrap_or_default();
+    match indexes.iter_mut().find(|i| i.info == reward.info) {
+        Some(reward_index) => reward_index.index += increment,
+        None => indexes.push(RewardIndex {
+            info: reward.info.clone(),
+            index: increment,
+        }),
+    }
+    REWARD_INDEXES.save(storage, &indexes)?;
+    Ok(())
+}
+
+/// Brings the rewards of a user holding `shares` up to the current indexes without saving.
+/// A user without checkpoint starts at the current indexes: shares that reached them without
+/// a checkpoint must not earn past distributions.
+fn accrue_user_rewards(
+    indexes: &[RewardIndex],
+    user_rewards: Option<Vec<UserReward>>,
+    shares: Uint128,
+) -> StdResult<Vec<UserReward>> {
+    let mut user_rewards = match user_rewards {
+        Some(user_rewards) => user_rewards,
+        None => {
+            return Ok(indexes
+                .iter()
+                .map(|reward_index| UserReward {
+                    info: reward_index.info.clone(),
+                    index: reward_index.index,
+                    pending: Uint128::zero(),
+                })
+                .collect())
+        }
+    };
+    for reward_index in indexes {
+        let position = user_rewards
+            .iter()
+            .position(|r| r.info == reward_index.info);
+        let user_reward = match position {
+            Some(position) => &mut user_rewards[position],
+            None => {
+                // a token added after the user's last checkpoint accrued from zero
+                user_rewards.push(UserReward {
+                    info: reward_index.info.clone(),
+                    index: Decimal256::zero(),
+                    pending: Uint128::zero(),
+                });
+                user_rewards.last_mut().unwrap()
+            }
+        };
+        let accrued: Uint128 =
+            (Uint256::from(shares) * (reward_index.index - user_reward.index)).try_into()?;
+        user_reward.pending = user_reward.pending.checked_add(accrued)?;
+        user_reward.index = reward_index.index;
+    }
+    Ok(user_rewards)
+}
+
+/// Checkpoints the rewards of `user` at the current indexes. Must be called with the share
+/// balance held *before* any change to it.
+pub fn checkpoint_user_rewards(
+    storage: &mut dyn Storage,
+    user: &Addr,
+    shares: Uint128,
+) -> StdResult<Vec<UserReward>> {
+    let indexes = REWARD_INDEXES.may_load(storage)?.unwrap_or_default();
+    let user_rewards =
+        accrue_user_rewards(&indexes, USER_REWARDS.may_load(storage, user)?, shares)?;
+    USER_REWARDS.save(storage, user, &user_rewards)?;
+    Ok(user_rewards)
+}
+
+/// Checkpoints both sides of a strategy token transfer. Only the cw20 strategy token can send
+/// the hook, the balances it reports are trusted.
+pub fn checkpoint_share_transfer(
+    storage: &mut dyn Storage,
+    sender: &Addr,
+    transfer: &StrategyTokenHookMsg,
+) -> Result<(), ContractError> {
+    match STRATEGY_TOKEN.load(storage)? {
+        AssetInfo::Token { contract_addr } if contract_addr == *sender => {}
+        _ => return Err(ContractError::Unauthorized {}),
+    }
+    let StrategyTokenHookMsg::SharesTransferred {
+        from,
+        to,
+        from_balance,
+        to_balance,
+        ..
+    } = transfer;
+    // both addresses were validated by the strategy token
+    checkpoint_user_rewards(storage, &Addr::unchecked(from), *from_balance)?;
+    checkpoint_user_rewards(storage, &Addr::unchecked(to), *to_balance)?;
+    Ok(())
+}
+
+/// Rewards `user` holding `shares` could claim right now.
+pub fn claimable_rewards(
+    storage: &dyn Storage,
+    user: &Addr,
+    shares: Uint128,
+) -> StdResult<Vec<Asset>> {
+    let indexes = REWARD_INDEXES.may_load(storage)?.unwrap_or_default();
+    let user_rewards =
+        accrue_user_rewards(&indexes, USER_REWARDS.may_load(storage, user)?, shares)?;
+    Ok(user_rewards
+        .into_iter()
+        .filter(|r| !r.pending.is_zero())
+        .map(|r| r.info.to_asset(r.pending))
+        .collect())
+}
+
+/// Pays out all rewards accrued by `user` holding `shares` to `recipient`.
+pub fn claim_rewards(
+    storage: &mut dyn Storage,
+    user: &Addr,
+    shares: Uint128,
+    recipient: &Addr,
+) -> Result<Response, ContractError> {
+    let mut user_rewards = checkpoint_user_rewards(storage, user, shares)?;
+
+    let mut response = Response::new();
+    let mut event = Event::new(CLAIM_REWARDS_EVENT)
+        .add_attribute("user", user.to_string())
+        .add_attribute("recipient", recipient.to_string());
+    for user_reward in user_rewards.iter_mut().filter(|r| !r.pending.is_zero()) {
+        let reward = user_reward.info.clone().to_asset(user_reward.pending);
+        event = event.add_attribute("reward", reward.to_string());
+        response = response.add_message(reward.into_msg(recipient)?);
+        user_reward.pending = Uint128::zero();
+    }
+    USER_REWARDS.save(storage, user, &user_rewards)?;
+
+    Ok(response.add_event(event))
+}
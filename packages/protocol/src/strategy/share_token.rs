@@ -0,0 +1,234 @@
+use apollo_asset::asset::AssetInfo;
+use cosmwasm_std::{
+    to_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Deps, Env, QuerierWrapper, ReplyOn,
+    StdError, StdResult, Storage, SubMsg, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, MinterResponse};
+use cw20_base::msg::InstantiateMsg as Cw20InstantiateMsg;
+use cw_storage_plus::Item;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    querier::query_cw20_token_info, strategy_token::InstantiateMsg as StrategyTokenInstantiateMsg,
+};
+
+use super::{
+    reply::REPLY_SAVE_STRATEGY_TOKEN_ADDR, reward::checkpoint_user_rewards, state::STRATEGY_TOKEN,
+};
+
+/// Supply of token-factory shares, which cannot be queried from the bank module on cosmwasm 1.0
+pub const SHARE_TOKEN_SUPPLY: Item<Uint128> = Item::new("share_token_supply");
+// TODO - remove after all strategies are migrated to `AssetInfo` strategy tokens
+pub const LEGACY_STRATEGY_TOKEN: Item<Addr> = Item::new("strategy_token");
+
+const MSG_CREATE_DENOM: &str = "/osmosis.tokenfactory.v1beta1.MsgCreateDenom";
+const MSG_MINT: &str = "/osmosis.tokenfactory.v1beta1.MsgMint";
+const MSG_BURN: &str = "/osmosis.tokenfactory.v1beta1.MsgBurn";
+
+/// Backend for the strategy shares, selected at instantiate time
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ShareTokenInit {
+    /// Instantiate a cw20 strategy token, the address is saved in the reply
+    Cw20 {
+        code_id: u64,
+        name: String,
+        symbol: String,
+    },
+    /// Create the token-factory denom `factory/{strategy}/{subdenom}`. Bank transfers of
+    /// native shares do not call back into the strategy, so there is no transfer hook and no
+    /// reward distribution. Shares are redeemed with the native `Redeem` message.
+    TokenFactory { subdenom: String },
+}
+
+/// Saves the strategy token when known up front and returns the messages creating it.
+pub fn init_share_token(
+    storage: &mut dyn Storage,
+    env: &Env,
+    init: ShareTokenInit,
+    apollo_factory: String,
+    strategy_id: u64,
+) -> StdResult<Vec<SubMsg>> {
+    match init {
+        ShareTokenInit::Cw20 {
+            code_id,
+            name,
+            symbol,
+        } => Ok(vec![SubMsg {
+            msg: WasmMsg::Instantiate {
+                admin: Some(apollo_factory.clone()),
+                code_id,
+                msg: to_binary(&StrategyTokenInstantiateMsg {
+                    base_init_msg: Cw20InstantiateMsg {
+                        name,
+                        symbol,
+                        decimals: 6,
+                        initial_balances: vec![],
+                        mint: Some(MinterResponse {
+                            minter: env.contract.address.to_string(),
+                            cap: None,
+                        }),
+                        marketing: None,
+                    },
+                    apollo_factory,
+                    strategy_id,
+                })?,
+                funds: vec![],
+                label: format!("apollo strategy token {}", strategy_id),
+            }
+            .into(),
+            gas_limit: None,
+            id: REPLY_SAVE_STRATEGY_TOKEN_ADDR,
+            reply_on: ReplyOn::Always,
+        }]),
+        ShareTokenInit::TokenFactory { subdenom } => {
+            let denom = format!("factory/{}/{}", env.contract.address, subdenom);
+            STRATEGY_TOKEN.save(storage, &AssetInfo::NativeToken { denom })?;
+            SHARE_TOKEN_SUPPLY.save(storage, &Uint128::zero())?;
+            Ok(vec![SubMsg::new(CosmosMsg::Stargate {
+                type_url: MSG_CREATE_DENOM.to_string(),
+                value: encode_msg_create_denom(env.contract.address.as_str(), &subdenom),
+            })])
+        }
+    }
+}
+
+/// Messages minting `amount` shares to `recipient`, whose rewards are checkpointed at the
+/// balance held before the mint.
+pub fn mint_shares(
+    querier: &QuerierWrapper,
+    storage: &mut dyn Storage,
+    env: &Env,
+    recipient: &Addr,
+    amount: Uint128,
+) -> StdResult<Vec<CosmosMsg>> {
+    let shares = query_share_balance(querier, storage, recipient.clone())?;
+    checkpoint_user_rewards(storage, recipient, shares)?;
+    match STRATEGY_TOKEN.load(storage)? {
+        AssetInfo::Token { contract_addr } => Ok(vec![WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Mint {
+                recipient: recipient.to_string(),
+                amount,
+            })?,
+            funds: vec![],
+        }
+        .into()]),
+        AssetInfo::NativeToken { denom } => {
+            SHARE_TOKEN_SUPPLY.update(storage, |supply| -> StdResult<_> {
+                Ok(supply.checked_add(amount)?)
+            })?;
+            let coin = Coin { denom, amount };
+            Ok(vec![
+                CosmosMsg::Stargate {
+                    type_url: MSG_MINT.to_string(),
+                    value: encode_msg_mint_or_burn(env.contract.address.as_str(), &coin),
+                },
+                BankMsg::Send {
+                    to_address: recipient.to_string(),
+                    amount: vec![coin],
+                }
+                .into(),
+            ])
+        }
+    }
+}
+
+/// Messages burning `amount` shares `owner` sent to the strategy. The rewards of `owner` are
+/// checkpointed at the balance held before sending them.
+pub fn burn_shares(
+    querier: &QuerierWrapper,
+    storage: &mut dyn Storage,
+    env: &Env,
+    owner: &Addr,
+    amount: Uint128,
+) -> StdResult<Vec<CosmosMsg>> {
+    let shares = query_share_balance(querier, storage, owner.clone())?.checked_add(amount)?;
+    checkpoint_user_rewards(storage, owner, shares)?;
+    match STRATEGY_TOKEN.load(storage)? {
+        AssetInfo::Token { contract_addr } => Ok(vec![WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Burn { amount })?,
+            funds: vec![],
+        }
+        .into()]),
+        AssetInfo::NativeToken { denom } => {
+            SHARE_TOKEN_SUPPLY.update(storage, |supply| -> StdResult<_> {
+                Ok(supply.checked_sub(amount)?)
+            })?;
+            Ok(vec![CosmosMsg::Stargate {
+                type_url: MSG_BURN.to_string(),
+                value: encode_msg_mint_or_burn(
+                    env.contract.address.as_str(),
+                    &Coin { denom, amount },
+                ),
+            }])
+        }
+    }
+}
+
+/// Total amount of shares issued by the strategy.
+pub fn query_share_supply(deps: Deps) -> StdResult<Uint128> {
+    match STRATEGY_TOKEN.load(deps.storage)? {
+        AssetInfo::Token { contract_addr } => {
+            Ok(query_cw20_token_info(&deps.querier, contract_addr)?.total_supply)
+        }
+        AssetInfo::NativeToken { .. } => SHARE_TOKEN_SUPPLY.load(deps.storage),
+    }
+}
+
+/// Shares held by `address`.
+pub fn query_share_balance(
+    querier: &QuerierWrapper,
+    storage: &dyn Storage,
+    address: Addr,
+) -> StdResult<Uint128> {
+    STRATEGY_TOKEN
+        .load(storage)?
+        .query_balance(querier, address)
+}
+
+/// Converts the cw20 address stored by strategies deployed before share tokens became an
+/// `AssetInfo`.
+pub fn migrate_strategy_token(storage: &mut dyn Storage) -> StdResult<AssetInfo> {
+    if let Ok(share_token) = STRATEGY_TOKEN.load(storage) {
+        return Ok(share_token);
+    }
+    let contract_addr = LEGACY_STRATEGY_TOKEN
+        .load(storage)
+        .map_err(|_| StdError::generic_err("strategy token not found"))?;
+    let share_token = AssetInfo::Token { contract_addr };
+    STRATEGY_TOKEN.save(storage, &share_token)?;
+    Ok(share_token)
+}
+
+/// Appends a length-delimited protobuf field.
+fn encode_bytes_field(buf: &mut Vec<u8>, field_number: u8, value: &[u8]) {
+    buf.push(field_number << 3 | 2);
+    let mut len = value.len();
+    while len >= 0x80 {
+        buf.push((len as u8 & 0x7f) | 0x80);
+        len >>= 7;
+    }
+    buf.push(len as u8);
+    buf.extend_from_slice(value);
+}
+
+pub(crate) fn encode_msg_create_denom(sender: &str, subdenom: &str) -> Binary {
+    let mut buf = vec![];
+    encode_bytes_field(&mut buf, 1, sender.as_bytes());
+    encode_bytes_field(&mut buf, 2, subdenom.as_bytes());
+    Binary::from(buf)
+}
+
+pub(crate) fn encode_msg_mint_or_burn(sender: &str, coin: &Coin) -> Binary {
+    let mut amount = vec![];
+    encode_bytes_field(&mut amount, 1, coin.denom.as_bytes());
+    encode_bytes_field(&mut amount, 2, coin.amount.to_string().as_bytes());
+
+    let mut buf = vec![];
+    encode_bytes_field(&mut buf, 1, sender.as_bytes());
+    encode_bytes_field(&mut buf, 2, &amount);
+    Binary::from(buf)
+}
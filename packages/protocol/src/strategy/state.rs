@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 
 pub const BASE_TOKEN: Item<AssetInfo> = Item::new("base_token");
 pub const BASE_DENOM: Item<AssetInfo> = Item::new("base_denom");
-pub const STRATEGY_TOKEN: Item<Addr> = Item::new("strategy_token");
+pub const STRATEGY_TOKEN: Item<AssetInfo> = Item::new("share_token");
 pub const STAKING_ADAPTOR: Item<Addr> = Item::new("staking_adaptor");
 pub const FACTORY: Item<Addr> = Item::new("factory");
 pub const ORACLE: Item<Addr> = Item::new("oracle");
@@ -17,7 +17,7 @@ pub const ORACLE: Item<Addr> = Item::new("oracle");
 pub struct BaseConfig {
     pub base_token: AssetInfo,
     pub base_denom: AssetInfo,
-    pub strategy_token: Addr,
+    pub strategy_token: AssetInfo,
     pub proxy: Addr,
     pub factory: Addr,
     pub oracle: Addr,
@@ -29,7 +29,7 @@ pub struct BaseConfig {
 pub struct ConfigResponse<C> {
     pub base_token: AssetInfo,
     pub base_denom: AssetInfo,
-    pub strategy_token: Addr,
+    pub strategy_token: AssetInfo,
     pub proxy: Addr,
     pub factory: Addr,
     pub oracle: Addr,
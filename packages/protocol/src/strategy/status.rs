@@ -0,0 +1,313 @@
+use std::fmt;
+
+use cosmwasm_std::{
+    from_binary, to_binary, Addr, DepsMut, Env, Event, QuerierWrapper, ReplyOn, Response, StdError,
+    StdResult, Storage, SubMsg, Uint128, WasmMsg,
+};
+use cw_storage_plus::Item;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ContractError;
+
+use super::{
+    msg::{BaseStrategyExecuteMsg, StrategyCw20HookMsg},
+    reply::REPLY_EMERGENCY_UNWIND,
+    share_token::{burn_shares, query_share_supply},
+    state::{BASE_TOKEN, FACTORY, STAKING_ADAPTOR},
+    unbonding::settle_emergency_unbonding,
+};
+
+pub const STRATEGY_STATUS_EVENT: &str = "apollo/strategy_status";
+pub const EMERGENCY_REDEEM_EVENT: &str = "apollo/emergency_redeem";
+
+pub const STRATEGY_STATUS: Item<StrategyStatus> = Item::new("strategy_status");
+pub const GUARDIAN: Item<Addr> = Item::new("guardian");
+pub const EMERGENCY_UNWIND: Item<EmergencyUnwind> = Item::new("emergency_unwind");
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StrategyStatus {
+    Active,
+    DepositsPaused,
+    WithdrawalsPaused,
+    Paused,
+    /// Winding down: users can only withdraw
+    Deprecated,
+    /// Funds were pulled from the staking adaptor, users redeem them pro-rata. Terminal.
+    Emergency,
+}
+
+/// What an execute entry point is about to do, checked against the status.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StrategyAction {
+    Deposit,
+    Withdraw,
+    Execute,
+    EmergencyRedeem,
+}
+
+/// Who is changing the status. The guardian can only tighten restrictions short of the
+/// emergency, the owner (the factory) can also lift them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatusRole {
+    Owner,
+    Guardian,
+}
+
+/// Snapshot taken once the emergency unwind has returned the funds.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct EmergencyUnwind {
+    pub total_base_amount: Uint128,
+    pub total_shares: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct StatusResponse {
+    pub status: StrategyStatus,
+    pub guardian: Option<Addr>,
+    pub emergency_unwind: Option<EmergencyUnwind>,
+}
+
+/// Message every staking adaptor accepts to unbond everything and send it to the sender.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[schemars(deny_unknown_fields)]
+pub enum StakingAdaptorEmergencyMsg {
+    EmergencyWithdraw {},
+}
+
+impl fmt::Display for StrategyStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let status = match self {
+            StrategyStatus::Active => "active",
+            StrategyStatus::DepositsPaused => "deposits_paused",
+            StrategyStatus::WithdrawalsPaused => "withdrawals_paused",
+            StrategyStatus::Paused => "paused",
+            StrategyStatus::Deprecated => "deprecated",
+            StrategyStatus::Emergency => "emergency",
+        };
+        write!(f, "{}", status)
+    }
+}
+
+impl fmt::Display for StrategyAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let action = match self {
+            StrategyAction::Deposit => "deposit",
+            StrategyAction::Withdraw => "withdraw",
+            StrategyAction::Execute => "execute",
+            StrategyAction::EmergencyRedeem => "emergency_redeem",
+        };
+        write!(f, "{}", action)
+    }
+}
+
+impl StrategyStatus {
+    pub fn allows(&self, action: StrategyAction) -> bool {
+        use StrategyAction::*;
+        match self {
+            StrategyStatus::Active => action != EmergencyRedeem,
+            StrategyStatus::DepositsPaused => matches!(action, Withdraw | Execute),
+            StrategyStatus::WithdrawalsPaused => matches!(action, Deposit | Execute),
+            StrategyStatus::Paused => false,
+            StrategyStatus::Deprecated => action == Withdraw,
+            StrategyStatus::Emergency => action == EmergencyRedeem,
+        }
+    }
+
+    /// Whether `role` may move the strategy from `self` to `to`.
+    pub fn can_transition_to(&self, to: StrategyStatus, role: StatusRole) -> bool {
+        use StrategyStatus::*;
+        match (self, to, role) {
+            (Emergency, _, _) => false,
+            (from, to, _) if *from == to => false,
+            // irreversible and forces an unwind, the guardian can only pause
+            (_, Emergency, role) => role == StatusRole::Owner,
+            (Deprecated, _, _) => false,
+            (_, Deprecated, role) => role == StatusRole::Owner,
+            (_, _, StatusRole::Owner) => true,
+            (Active, _, StatusRole::Guardian) => true,
+            (DepositsPaused, Paused, StatusRole::Guardian)
+            | (WithdrawalsPaused, Paused, StatusRole::Guardian) => true,
+            _ => false,
+        }
+    }
+}
+
+pub fn load_status(storage: &dyn Storage) -> StdResult<StrategyStatus> {
+    Ok(STRATEGY_STATUS
+        .may_load(storage)?
+        .unwrap_or(StrategyStatus::Active))
+}
+
+/// Guard to call at the start of every execute entry point performing `action`.
+pub fn assert_action_allowed(
+    storage: &dyn Storage,
+    action: StrategyAction,
+) -> Result<(), ContractError> {
+    let status = load_status(storage)?;
+    if !status.allows(action) {
+        return Err(ContractError::ActionNotAllowed {
+            action: action.to_string(),
+            status: status.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Guard for the base execute messages. Strategy specific messages are left to the strategy.
+pub fn assert_base_execute_allowed<S>(
+    storage: &dyn Storage,
+    msg: &BaseStrategyExecuteMsg<S>,
+) -> Result<(), ContractError> {
+    match msg {
+        BaseStrategyExecuteMsg::ExecuteStrategy { .. } => {
+            assert_action_allowed(storage, StrategyAction::Execute)
+        }
+        BaseStrategyExecuteMsg::Redeem { .. } | BaseStrategyExecuteMsg::StartUnbonding {} => {
+            assert_action_allowed(storage, StrategyAction::Withdraw)
+        }
+        BaseStrategyExecuteMsg::EmergencyRedeem { .. } => {
+            assert_action_allowed(storage, StrategyAction::EmergencyRedeem)
+        }
+        BaseStrategyExecuteMsg::Deposit { .. } | BaseStrategyExecuteMsg::ZapIn { .. } => {
+            assert_action_allowed(storage, StrategyAction::Deposit)
+        }
+        BaseStrategyExecuteMsg::Receive(msg) => {
+            let hook: StrategyCw20HookMsg = from_binary(&msg.msg)?;
+            assert_action_allowed(storage, hook.action())
+        }
+        // shares of unbonding users are already burned and accrued rewards are already owed,
+        // both must stay claimable, also once the strategy is in emergency
+        BaseStrategyExecuteMsg::Claim { .. }
+        | BaseStrategyExecuteMsg::ClaimUnbonded { .. }
+        | BaseStrategyExecuteMsg::StrategyTokenHook(_)
+        | BaseStrategyExecuteMsg::UpdateStatus { .. }
+        | BaseStrategyExecuteMsg::UpdateGuardian { .. }
+        | BaseStrategyExecuteMsg::Strategy(_) => Ok(()),
+    }
+}
+
+pub fn sender_role(storage: &dyn Storage, sender: &Addr) -> Result<StatusRole, ContractError> {
+    if *sender == FACTORY.load(storage)? {
+        Ok(StatusRole::Owner)
+    } else if GUARDIAN.may_load(storage)?.as_ref() == Some(sender) {
+        Ok(StatusRole::Guardian)
+    } else {
+        Err(ContractError::Unauthorized {})
+    }
+}
+
+/// Moves the strategy to `to`. Entering `Emergency` asks the staking adaptor to return all
+/// funds, the pro-rata snapshot is taken in the reply.
+pub fn update_status(
+    storage: &mut dyn Storage,
+    sender: &Addr,
+    to: StrategyStatus,
+) -> Result<Response, ContractError> {
+    let role = sender_role(storage, sender)?;
+    let from = load_status(storage)?;
+    if !from.can_transition_to(to, role) {
+        return Err(ContractError::InvalidStatusTransition {
+            from: from.to_string(),
+            to: to.to_string(),
+        });
+    }
+    STRATEGY_STATUS.save(storage, &to)?;
+
+    let mut response = Response::new().add_event(
+        Event::new(STRATEGY_STATUS_EVENT)
+            .add_attribute("sender", sender.to_string())
+            .add_attribute("from", from.to_string())
+            .add_attribute("to", to.to_string()),
+    );
+    if to == StrategyStatus::Emergency {
+        response = response.add_submessage(SubMsg {
+            msg: WasmMsg::Execute {
+                contract_addr: STAKING_ADAPTOR.load(storage)?.to_string(),
+                msg: to_binary(&StakingAdaptorEmergencyMsg::EmergencyWithdraw {})?,
+                funds: vec![],
+            }
+            .into(),
+            gas_limit: None,
+            id: REPLY_EMERGENCY_UNWIND,
+            reply_on: ReplyOn::Success,
+        });
+    }
+    Ok(response)
+}
+
+/// Only the owner can appoint or remove the guardian.
+pub fn update_guardian(
+    storage: &mut dyn Storage,
+    sender: &Addr,
+    guardian: Option<Addr>,
+) -> Result<Response, ContractError> {
+    if sender_role(storage, sender)? != StatusRole::Owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    match &guardian {
+        Some(guardian) => GUARDIAN.save(storage, guardian)?,
+        None => GUARDIAN.remove(storage),
+    }
+    Ok(Response::new().add_attribute(
+        "guardian",
+        guardian.map(|g| g.to_string()).unwrap_or_default(),
+    ))
+}
+
+/// Snapshots the recovered base tokens and the share supply once the unwind has executed.
+/// Base tokens owed to unbonding claims are left out, they belong to burned shares.
+pub fn record_emergency_unwind(deps: DepsMut, env: &Env) -> Result<Response, ContractError> {
+    let balance = BASE_TOKEN
+        .load(deps.storage)?
+        .query_balance(&deps.querier, env.contract.address.clone())?;
+    let total_base_amount = balance.saturating_sub(settle_emergency_unbonding(deps.storage, env)?);
+    let total_shares = query_share_supply(deps.as_ref())?;
+    EMERGENCY_UNWIND.save(
+        deps.storage,
+        &EmergencyUnwind {
+            total_base_amount,
+            total_shares,
+        },
+    )?;
+    Ok(Response::new()
+        .add_attribute("action", "emergency_unwind")
+        .add_attribute("total_base_amount", total_base_amount)
+        .add_attribute("total_shares", total_shares))
+}
+
+/// Burns `shares` already sent to the strategy by `owner` and pays `recipient` their pro-rata
+/// part of the unwound funds. The snapshot is fixed so the redemption order does not matter.
+pub fn emergency_redeem(
+    querier: &QuerierWrapper,
+    storage: &mut dyn Storage,
+    env: &Env,
+    owner: &Addr,
+    recipient: &Addr,
+    shares: Uint128,
+) -> Result<Response, ContractError> {
+    assert_action_allowed(storage, StrategyAction::EmergencyRedeem)?;
+    if shares.is_zero() {
+        return Err(StdError::generic_err("No shares to redeem").into());
+    }
+    let unwind = EMERGENCY_UNWIND.load(storage)?;
+    let amount = unwind
+        .total_base_amount
+        .multiply_ratio(shares, unwind.total_shares);
+    let payout = BASE_TOKEN.load(storage)?.to_asset(amount);
+
+    Ok(Response::new()
+        .add_messages(burn_shares(querier, storage, env, owner, shares)?)
+        .add_message(payout.clone().into_msg(recipient)?)
+        .add_event(
+            Event::new(EMERGENCY_REDEEM_EVENT)
+                .add_attribute("recipient", recipient.to_string())
+                .add_attribute("shares", shares)
+                .add_attribute("payout", payout.to_string()),
+        ))
+}
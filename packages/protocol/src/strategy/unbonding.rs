@@ -0,0 +1,250 @@
+use cosmwasm_std::{
+    to_binary, Addr, CosmosMsg, Env, Event, Order, QuerierWrapper, Response, StdResult, Storage,
+    Uint128, WasmMsg,
+};
+use cw_storage_plus::{Item, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ContractError;
+
+use super::{
+    share_token::burn_shares,
+    state::{BASE_TOKEN, STAKING_ADAPTOR},
+};
+
+pub const WITHDRAWAL_REQUEST_EVENT: &str = "apollo/withdrawal_request";
+pub const UNBONDING_STARTED_EVENT: &str = "apollo/unbonding_started";
+pub const CLAIM_UNBONDED_EVENT: &str = "apollo/claim_unbonded";
+
+pub const UNBONDING_CONFIG: Item<UnbondingConfig> = Item::new("unbonding_config");
+/// Id of the batch collecting withdrawal requests until it can be unbonded.
+pub const OPEN_BATCH: Item<u64> = Item::new("unbonding_open_batch");
+pub const UNBONDING_BATCHES: Map<u64, UnbondingBatch> = Map::new("unbonding_batches");
+/// Base tokens owed to each user per batch, keyed by `(user, batch_id)`.
+pub const UNBONDING_CLAIMS: Map<(&Addr, u64), Uint128> = Map::new("unbonding_claims");
+/// Sum of all unpaid claims, whatever the state of their batch.
+pub const UNBONDING_OWED: Item<Uint128> = Item::new("unbonding_owed");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct UnbondingConfig {
+    pub unbonding_period: u64, // seconds
+    /// Unbondings the staking adaptor accepts at once, requests wait in the open batch beyond
+    pub max_concurrent_unbondings: Option<u32>,
+}
+
+/// Withdrawal requests unbonded together. `release_at` is set once the batch is sent to the
+/// staking adaptor.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct UnbondingBatch {
+    pub id: u64,
+    pub amount: Uint128,
+    pub release_at: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct UnbondingClaim {
+    pub batch_id: u64,
+    pub amount: Uint128,
+    pub release_at: Option<u64>, // not set while the batch waits for an unbonding slot
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ClaimsResponse {
+    pub claims: Vec<UnbondingClaim>,
+}
+
+/// Message every staking adaptor with an unbonding period accepts. Unbonded funds are sent
+/// back to the strategy once released.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[schemars(deny_unknown_fields)]
+pub enum StakingAdaptorUnbondMsg {
+    Unbond { amount: Uint128 },
+}
+
+fn open_batch(storage: &dyn Storage) -> StdResult<UnbondingBatch> {
+    let id = OPEN_BATCH.may_load(storage)?.unwrap_or_default();
+    Ok(UNBONDING_BATCHES
+        .may_load(storage, id)?
+        .unwrap_or(UnbondingBatch {
+            id,
+            ..Default::default()
+        }))
+}
+
+/// Batches still unbonding. Batches are started in id order with the same period, so the
+/// scan stops at the first released one.
+fn active_unbondings(storage: &dyn Storage, now: u64) -> StdResult<u32> {
+    let mut active = 0;
+    for item in UNBONDING_BATCHES.range(storage, None, None, Order::Descending) {
+        match item?.1.release_at {
+            Some(release_at) if release_at > now => active += 1,
+            Some(_) => break,
+            None => continue,
+        }
+    }
+    Ok(active)
+}
+
+/// Burns `shares` and records `amount` of base tokens for `user` in the open batch, then
+/// tries to start unbonding it.
+pub fn request_withdrawal(
+    querier: &QuerierWrapper,
+    storage: &mut dyn Storage,
+    env: &Env,
+    user: &Addr,
+    shares: Uint128,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if shares.is_zero() || amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+    let mut batch = open_batch(storage)?;
+    batch.amount = batch.amount.checked_add(amount)?;
+    UNBONDING_BATCHES.save(storage, batch.id, &batch)?;
+    UNBONDING_CLAIMS.update(storage, (user, batch.id), |claim| -> StdResult<_> {
+        Ok(claim.unwrap_or_default().checked_add(amount)?)
+    })?;
+    let owed = UNBONDING_OWED.may_load(storage)?.unwrap_or_default();
+    UNBONDING_OWED.save(storage, &owed.checked_add(amount)?)?;
+
+    let response = Response::new()
+        .add_messages(burn_shares(querier, storage, env, user, shares)?)
+        .add_event(
+            Event::new(WITHDRAWAL_REQUEST_EVENT)
+                .add_attribute("user", user)
+                .add_attribute("batch_id", batch.id.to_string())
+                .add_attribute("shares", shares)
+                .add_attribute("amount", amount),
+        );
+    Ok(match start_open_batch(storage, env)? {
+        Some((msg, event)) => response.add_message(msg).add_event(event),
+        None => response,
+    })
+}
+
+/// Sends the open batch to the staking adaptor if it holds requests and an unbonding slot is
+/// free. Anyone can call it once a previous unbonding is released.
+pub fn start_unbonding(storage: &mut dyn Storage, env: &Env) -> Result<Response, ContractError> {
+    let (msg, event) =
+        start_open_batch(storage, env)?.ok_or(ContractError::NoUnbondingToStart {})?;
+    Ok(Response::new().add_message(msg).add_event(event))
+}
+
+fn start_open_batch(storage: &mut dyn Storage, env: &Env) -> StdResult<Option<(CosmosMsg, Event)>> {
+    let config = UNBONDING_CONFIG.load(storage)?;
+    let now = env.block.time.seconds();
+    let mut batch = open_batch(storage)?;
+    if batch.amount.is_zero() {
+        return Ok(None);
+    }
+    if let Some(max) = config.max_concurrent_unbondings {
+        if active_unbondings(storage, now)? >= max {
+            return Ok(None);
+        }
+    }
+
+    let release_at = now + config.unbonding_period;
+    batch.release_at = Some(release_at);
+    UNBONDING_BATCHES.save(storage, batch.id, &batch)?;
+    OPEN_BATCH.save(storage, &(batch.id + 1))?;
+
+    let msg: CosmosMsg = WasmMsg::Execute {
+        contract_addr: STAKING_ADAPTOR.load(storage)?.to_string(),
+        msg: to_binary(&StakingAdaptorUnbondMsg::Unbond {
+            amount: batch.amount,
+        })?,
+        funds: vec![],
+    }
+    .into();
+    let event = Event::new(UNBONDING_STARTED_EVENT)
+        .add_attribute("batch_id", batch.id.to_string())
+        .add_attribute("amount", batch.amount)
+        .add_attribute("release_at", release_at.to_string());
+    Ok(Some((msg, event)))
+}
+
+/// Pays `recipient` every claim of `user` whose batch is released.
+pub fn claim_unbonded(
+    storage: &mut dyn Storage,
+    env: &Env,
+    user: &Addr,
+    recipient: &Addr,
+) -> Result<Response, ContractError> {
+    let now = env.block.time.seconds();
+    let mut amount = Uint128::zero();
+    for claim in query_claims(storage, user)?.claims {
+        if matches!(claim.release_at, Some(release_at) if release_at <= now) {
+            amount = amount.checked_add(claim.amount)?;
+            UNBONDING_CLAIMS.remove(storage, (user, claim.batch_id));
+        }
+    }
+    if amount.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+    let owed = UNBONDING_OWED.may_load(storage)?.unwrap_or_default();
+    UNBONDING_OWED.save(storage, &owed.checked_sub(amount)?)?;
+
+    let payout = BASE_TOKEN.load(storage)?.to_asset(amount);
+    Ok(Response::new()
+        .add_message(payout.clone().into_msg(recipient)?)
+        .add_event(
+            Event::new(CLAIM_UNBONDED_EVENT)
+                .add_attribute("user", user)
+                .add_attribute("recipient", recipient)
+                .add_attribute("payout", payout.to_string()),
+        ))
+}
+
+/// Called when the strategy unwinds in an emergency: the open batch came back with the rest of
+/// the staked funds, so it is released right away. Returns the base tokens held by the strategy
+/// that belong to claimants, i.e. everything owed but the batches still unbonding.
+pub fn settle_emergency_unbonding(storage: &mut dyn Storage, env: &Env) -> StdResult<Uint128> {
+    let now = env.block.time.seconds();
+    let owed = UNBONDING_OWED.may_load(storage)?.unwrap_or_default();
+    if owed.is_zero() {
+        return Ok(owed);
+    }
+
+    // same scan as `active_unbondings`, started batches are ordered by release time
+    let mut unbonding = Uint128::zero();
+    for item in UNBONDING_BATCHES.range(storage, None, None, Order::Descending) {
+        let batch = item?.1;
+        match batch.release_at {
+            Some(release_at) if release_at > now => {
+                unbonding = unbonding.checked_add(batch.amount)?
+            }
+            Some(_) => break,
+            None => continue,
+        }
+    }
+
+    let mut batch = open_batch(storage)?;
+    if !batch.amount.is_zero() {
+        batch.release_at = Some(now);
+        UNBONDING_BATCHES.save(storage, batch.id, &batch)?;
+        OPEN_BATCH.save(storage, &(batch.id + 1))?;
+    }
+    Ok(owed.checked_sub(unbonding)?)
+}
+
+pub fn query_claims(storage: &dyn Storage, user: &Addr) -> StdResult<ClaimsResponse> {
+    let claims = UNBONDING_CLAIMS
+        .prefix(user)
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (batch_id, amount) = item?;
+            Ok(UnbondingClaim {
+                batch_id,
+                amount,
+                release_at: UNBONDING_BATCHES.load(storage, batch_id)?.release_at,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ClaimsResponse { claims })
+}
@@ -0,0 +1,151 @@
+use crate::error::ContractError;
+use crate::strategy::deposit_limit::{
+    checked_convert_deposit, compute_max_deposit, release_user_deposit, DepositLimits,
+    DEPOSIT_ALLOWLIST, DEPOSIT_LIMITS, USER_DEPOSITS,
+};
+use cosmwasm_std::testing::mock_dependencies;
+use cosmwasm_std::{Addr, Decimal256, Empty, QuerierWrapper, Uint128};
+use test_case::test_case;
+
+#[test_case(None, None, false, 0 => Uint128::MAX.u128(); "no limits")]
+#[test_case(Some(10_000), None, false, 0 => 3_000; "tvl cap at price 2")]
+#[test_case(Some(1_000), None, false, 0 => 0; "tvl above cap")]
+#[test_case(None, Some(500), false, 200 => 300; "user cap")]
+#[test_case(Some(10_000), Some(5_000), false, 200 => 3_000; "lowest cap wins")]
+#[test_case(None, None, true, 0 => 0; "private strategy")]
+fn test_compute_max_deposit(
+    tvl_cap: Option<u128>,
+    user_cap: Option<u128>,
+    private: bool,
+    user_deposits: u128,
+) -> u128 {
+    // Given 2_000 base tokens bonded at a price of 2, i.e. a TVL of 4_000
+    let limits = DepositLimits {
+        tvl_cap: tvl_cap.map(Uint128::new),
+        user_cap: user_cap.map(Uint128::new),
+        private,
+    };
+
+    // When
+    compute_max_deposit(
+        &limits,
+        false,
+        Decimal256::percent(200),
+        Uint128::new(2_000),
+        Uint128::new(user_deposits),
+    )
+    .unwrap()
+    .u128()
+}
+
+#[test]
+fn test_checked_convert_deposit_enforces_allowlist() {
+    // Given
+    let mut deps = mock_dependencies();
+    let querier: QuerierWrapper<Empty> = QuerierWrapper::new(&deps.querier);
+    let insider = Addr::unchecked("insider");
+    DEPOSIT_LIMITS
+        .save(
+            &mut deps.storage,
+            &DepositLimits {
+                private: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    DEPOSIT_ALLOWLIST
+        .save(&mut deps.storage, &insider, &Empty {})
+        .unwrap();
+
+    // When
+    let outsider = checked_convert_deposit(
+        &querier,
+        &mut deps.storage,
+        &Addr::unchecked("outsider"),
+        Uint128::new(100),
+        Uint128::zero(),
+        Uint128::zero(),
+    );
+    let deposit = checked_convert_deposit(
+        &querier,
+        &mut deps.storage,
+        &insider,
+        Uint128::new(100),
+        Uint128::zero(),
+        Uint128::zero(),
+    )
+    .unwrap();
+
+    // Then
+    assert_eq!(
+        outsider,
+        Err(ContractError::DepositLimitExceeded {
+            max_deposit: Uint128::zero()
+        })
+    );
+    assert_eq!(deposit.shares, Uint128::new(100));
+}
+
+#[test]
+fn test_user_cap_counts_deposits_not_shares() {
+    // Given a user who deposited up to the cap, then transferred all their shares away
+    let mut deps = mock_dependencies();
+    let querier: QuerierWrapper<Empty> = QuerierWrapper::new(&deps.querier);
+    let user = Addr::unchecked("user");
+    DEPOSIT_LIMITS
+        .save(
+            &mut deps.storage,
+            &DepositLimits {
+                user_cap: Some(Uint128::new(100)),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    checked_convert_deposit(
+        &querier,
+        &mut deps.storage,
+        &user,
+        Uint128::new(100),
+        Uint128::zero(),
+        Uint128::zero(),
+    )
+    .unwrap();
+
+    // When
+    let err = checked_convert_deposit(
+        &querier,
+        &mut deps.storage,
+        &user,
+        Uint128::new(1),
+        Uint128::new(100),
+        Uint128::new(100),
+    )
+    .unwrap_err();
+
+    // Then
+    assert_eq!(
+        err,
+        ContractError::DepositLimitExceeded {
+            max_deposit: Uint128::zero()
+        }
+    );
+}
+
+#[test]
+fn test_release_user_deposit() {
+    // Given
+    let mut deps = mock_dependencies();
+    let user = Addr::unchecked("user");
+    USER_DEPOSITS
+        .save(&mut deps.storage, &user, &Uint128::new(100))
+        .unwrap();
+
+    // When
+    release_user_deposit(&mut deps.storage, &user, Uint128::new(40)).unwrap();
+    let partial = USER_DEPOSITS.load(&deps.storage, &user).unwrap();
+    release_user_deposit(&mut deps.storage, &user, Uint128::new(500)).unwrap();
+
+    // Then
+    assert_eq!(partial, Uint128::new(60));
+    assert!(!USER_DEPOSITS.has(&deps.storage, &user));
+}
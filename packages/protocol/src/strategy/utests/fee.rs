@@ -0,0 +1,135 @@
+use crate::error::ContractError;
+use crate::strategy::fee::{
+    compute_management_fee_shares, compute_performance_fee, convert_deposit, convert_withdrawal,
+    Fee, FeeCollection, FeeConfig, MAX_DEPOSIT_FEE, SECONDS_PER_YEAR,
+};
+use cosmwasm_std::{Addr, Decimal, Decimal256, Uint128};
+use test_case::test_case;
+
+#[test_case(FeeCollection::TransferAssets => (Uint128::new(2), Uint128::zero(), Decimal256::percent(118)); "transfer assets")]
+#[test_case(FeeCollection::MintShares => (Uint128::new(2), Uint128::new(1), Decimal256::from_ratio(120u128, 101u128)); "mint shares")]
+fn test_compute_performance_fee(collection: FeeCollection) -> (Uint128, Uint128, Decimal256) {
+    let accrual = compute_performance_fee(
+        Some(Decimal256::one()),
+        Decimal::percent(10),
+        &collection,
+        Uint128::new(120),
+        Uint128::new(100),
+    )
+    .unwrap();
+
+    assert_eq!(accrual.yield_amount, Uint128::new(20));
+    (
+        accrual.fee_amount,
+        accrual.fee_shares,
+        accrual.high_water_mark,
+    )
+}
+
+#[test]
+fn test_no_fee_below_high_water_mark() {
+    let accrual = compute_performance_fee(
+        Some(Decimal256::percent(150)),
+        Decimal::percent(10),
+        &FeeCollection::MintShares,
+        Uint128::new(120),
+        Uint128::new(100),
+    )
+    .unwrap();
+
+    assert!(accrual.fee_amount.is_zero());
+    assert_eq!(accrual.high_water_mark, Decimal256::percent(150));
+}
+
+#[test]
+fn test_first_accrual_sets_high_water_mark() {
+    let accrual = compute_performance_fee(
+        None,
+        Decimal::percent(10),
+        &FeeCollection::MintShares,
+        Uint128::new(120),
+        Uint128::new(100),
+    )
+    .unwrap();
+
+    assert!(accrual.fee_amount.is_zero());
+    assert_eq!(accrual.high_water_mark, Decimal256::percent(120));
+}
+
+fn fee(percent: u64) -> Option<Fee> {
+    Some(Fee {
+        rate: Decimal::percent(percent),
+        recipient: Addr::unchecked("treasury"),
+    })
+}
+
+#[test]
+fn test_convert_deposit_and_withdrawal_with_fees() {
+    let fees = FeeConfig {
+        management_fee: None,
+        deposit_fee: fee(1),
+        withdrawal_fee: fee(2),
+    };
+
+    let deposit = convert_deposit(
+        &fees,
+        Uint128::new(1_000),
+        Uint128::new(2_000),
+        Uint128::new(1_000),
+    )
+    .unwrap();
+    assert_eq!(deposit.fee_amount, Uint128::new(10));
+    assert_eq!(deposit.shares, Uint128::new(495));
+
+    let withdrawal = convert_withdrawal(
+        &fees,
+        Uint128::new(100),
+        Uint128::new(2_000),
+        Uint128::new(1_000),
+    )
+    .unwrap();
+    assert_eq!(withdrawal.gross_amount, Uint128::new(200));
+    assert_eq!(withdrawal.fee_amount, Uint128::new(4));
+    assert_eq!(withdrawal.net_amount, Uint128::new(196));
+}
+
+#[test]
+fn test_management_fee_accrues_pro_rata() {
+    let year =
+        compute_management_fee_shares(Decimal::percent(2), SECONDS_PER_YEAR, Uint128::new(980))
+            .unwrap();
+    assert_eq!(year, Uint128::new(20));
+
+    let none = compute_management_fee_shares(Decimal::percent(2), 0, Uint128::new(980)).unwrap();
+    assert!(none.is_zero());
+}
+
+#[test]
+fn test_management_fee_over_the_whole_strategy_fails() {
+    // 5% a year over 20 years is the whole strategy
+    let err = compute_management_fee_shares(
+        Decimal::percent(5),
+        20 * SECONDS_PER_YEAR,
+        Uint128::new(1_000),
+    )
+    .unwrap_err();
+
+    assert_eq!(err, ContractError::ManagementFeeOverflow {});
+}
+
+#[test]
+fn test_fee_caps() {
+    let fees = FeeConfig {
+        management_fee: None,
+        deposit_fee: fee(3),
+        withdrawal_fee: None,
+    };
+
+    assert_eq!(
+        fees.validate(),
+        Err(ContractError::FeeAboveCap {
+            rate: Decimal::percent(3),
+            cap: MAX_DEPOSIT_FEE,
+        })
+    );
+}
@@ -0,0 +1,101 @@
+use crate::error::ContractError;
+use crate::strategy::keeper::{
+    check_execution_cooldown, compute_keeper_reward, pay_keeper_reward, KeeperRewardConfig,
+    KEEPER_PAYOUTS, KEEPER_REWARD_CONFIG,
+};
+use crate::utests::fixtures::native;
+use cosmwasm_std::testing::{mock_dependencies, mock_env};
+use cosmwasm_std::{Addr, Decimal, Decimal256, Uint128};
+use test_case::test_case;
+
+fn config(max_reward_value: u128) -> KeeperRewardConfig {
+    KeeperRewardConfig {
+        reward_share: Decimal::percent(1),
+        max_reward_value: Uint128::new(max_reward_value),
+        cooldown: 60,
+    }
+}
+
+#[test_case(1_000_000 => (vec![1_000, 2_000], 5_000); "under the cap")]
+#[test_case(5_000 => (vec![1_000, 2_000], 5_000); "at the cap")]
+#[test_case(1_000 => (vec![200, 400], 1_000); "scaled to the cap")]
+fn test_compute_keeper_reward(max_reward_value: u128) -> (Vec<u128>, u128) {
+    // Given 100_000 uatom at 1 and 200_000 uosmo at 2, the uncapped reward is worth 5_000
+    let harvested = [
+        (native("uatom", 100_000), Decimal256::one()),
+        (native("uosmo", 200_000), Decimal256::percent(200)),
+    ];
+
+    // When
+    let reward = compute_keeper_reward(&config(max_reward_value), &harvested).unwrap();
+
+    // Then
+    (
+        reward.assets.iter().map(|a| a.amount.u128()).collect(),
+        reward.value.u128(),
+    )
+}
+
+#[test]
+fn test_execution_cooldown() {
+    // Given
+    let mut deps = mock_dependencies();
+    let mut env = mock_env();
+    KEEPER_REWARD_CONFIG
+        .save(&mut deps.storage, &config(1_000))
+        .unwrap();
+    check_execution_cooldown(&mut deps.storage, &env).unwrap();
+    let next_execution = env.block.time.seconds() + 60;
+
+    // When
+    env.block.time = env.block.time.plus_seconds(59);
+    let early = check_execution_cooldown(&mut deps.storage, &env);
+    env.block.time = env.block.time.plus_seconds(1);
+    let on_time = check_execution_cooldown(&mut deps.storage, &env);
+
+    // Then
+    assert_eq!(
+        early,
+        Err(ContractError::ExecutionCooldown { next_execution })
+    );
+    assert_eq!(on_time, Ok(()));
+}
+
+#[test]
+fn test_execution_cooldown_overflow_fails() {
+    // Given
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let mut config = config(1_000);
+    config.cooldown = u64::MAX;
+    KEEPER_REWARD_CONFIG
+        .save(&mut deps.storage, &config)
+        .unwrap();
+    check_execution_cooldown(&mut deps.storage, &env).unwrap();
+
+    // When
+    let err = check_execution_cooldown(&mut deps.storage, &env).unwrap_err();
+
+    // Then
+    assert!(matches!(err, ContractError::OverflowError(_)));
+}
+
+#[test]
+fn test_pay_keeper_reward_accumulates_payouts() {
+    // Given
+    let mut deps = mock_dependencies();
+    let keeper = Addr::unchecked("keeper");
+    let harvested = [(native("uatom", 100_000), Decimal256::one())];
+    let reward = compute_keeper_reward(&config(1_000_000), &harvested).unwrap();
+
+    // When
+    pay_keeper_reward(&mut deps.storage, &keeper, reward.clone()).unwrap();
+    let res = pay_keeper_reward(&mut deps.storage, &keeper, reward).unwrap();
+
+    // Then
+    let payouts = KEEPER_PAYOUTS.load(&deps.storage).unwrap();
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(payouts.executions, 2);
+    assert_eq!(payouts.total_value, Uint128::new(2_000));
+    assert_eq!(payouts.assets, vec![native("uatom", 2_000)]);
+}
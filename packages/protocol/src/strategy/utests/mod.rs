@@ -1,2 +1,10 @@
+mod deposit_limit;
+mod fee;
+mod keeper;
 mod querier;
+mod receive;
 mod reply;
+mod reward;
+mod share_token;
+mod status;
+mod unbonding;
@@ -0,0 +1,291 @@
+use crate::error::ContractError;
+use crate::strategy::msg::{BaseStrategyExecuteMsg, StrategyCw20HookMsg};
+use crate::strategy::receive::{
+    parse_cw20_hook, parse_native_deposit, parse_native_redeem, parse_native_zap_in, ReceiveAction,
+    ZAP_IN_ASSETS,
+};
+use crate::strategy::state::{BASE_TOKEN, STRATEGY_TOKEN};
+use crate::strategy::status::{assert_base_execute_allowed, StrategyStatus, STRATEGY_STATUS};
+use apollo_asset::asset::AssetInfo;
+use cosmwasm_std::testing::{mock_dependencies, mock_info, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{to_binary, Addr, Coin, OwnedDeps, Uint128};
+use cw20::Cw20ReceiveMsg;
+use test_case::test_case;
+
+fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+    let mut deps = mock_dependencies();
+    let token = |addr: &str| AssetInfo::Token {
+        contract_addr: Addr::unchecked(addr),
+    };
+    BASE_TOKEN.save(&mut deps.storage, &token("lp")).unwrap();
+    STRATEGY_TOKEN
+        .save(&mut deps.storage, &token("shares"))
+        .unwrap();
+    ZAP_IN_ASSETS
+        .save(&mut deps.storage, &vec![token("astro")])
+        .unwrap();
+    deps
+}
+
+fn receive_msg(hook: &StrategyCw20HookMsg) -> Cw20ReceiveMsg {
+    Cw20ReceiveMsg {
+        sender: "user".to_string(),
+        amount: Uint128::new(100),
+        msg: to_binary(hook).unwrap(),
+    }
+}
+
+#[test]
+fn test_parse_cw20_hook() {
+    // Given
+    let deps = setup();
+    let user = Addr::unchecked("user");
+
+    // When
+    let deposit = parse_cw20_hook(
+        &deps.storage,
+        &deps.api,
+        &mock_info("lp", &[]),
+        receive_msg(&StrategyCw20HookMsg::Deposit { recipient: None }),
+    )
+    .unwrap();
+    let redeem = parse_cw20_hook(
+        &deps.storage,
+        &deps.api,
+        &mock_info("shares", &[]),
+        receive_msg(&StrategyCw20HookMsg::Redeem {
+            recipient: Some("friend".to_string()),
+        }),
+    )
+    .unwrap();
+    let zap_in = parse_cw20_hook(
+        &deps.storage,
+        &deps.api,
+        &mock_info("astro", &[]),
+        receive_msg(&StrategyCw20HookMsg::ZapIn {
+            recipient: None,
+            min_shares: Some(Uint128::new(90)),
+        }),
+    )
+    .unwrap();
+
+    // Then
+    assert_eq!(
+        deposit,
+        ReceiveAction::Deposit {
+            recipient: user.clone(),
+            amount: Uint128::new(100),
+        }
+    );
+    assert_eq!(
+        redeem,
+        ReceiveAction::Redeem {
+            owner: user.clone(),
+            recipient: Addr::unchecked("friend"),
+            shares: Uint128::new(100),
+        }
+    );
+    assert_eq!(
+        zap_in,
+        ReceiveAction::ZapIn {
+            recipient: user,
+            offer: AssetInfo::Token {
+                contract_addr: Addr::unchecked("astro"),
+            }
+            .to_asset(Uint128::new(100)),
+            min_shares: Some(Uint128::new(90)),
+        }
+    );
+}
+
+#[test_case("shares", StrategyCw20HookMsg::Deposit { recipient: None }; "deposit shares")]
+#[test_case("astro", StrategyCw20HookMsg::Deposit { recipient: None }; "deposit zap asset")]
+#[test_case("lp", StrategyCw20HookMsg::Redeem { recipient: None }; "redeem base token")]
+#[test_case("lp", StrategyCw20HookMsg::EmergencyRedeem { recipient: None }; "emergency redeem base token")]
+#[test_case("lp", StrategyCw20HookMsg::ZapIn { recipient: None, min_shares: None }; "zap in base token")]
+#[test_case("scam", StrategyCw20HookMsg::ZapIn { recipient: None, min_shares: None }; "zap in unknown token")]
+fn test_parse_cw20_hook_rejects_unexpected_token(token: &str, hook: StrategyCw20HookMsg) {
+    // Given
+    let deps = setup();
+
+    // When
+    let err = parse_cw20_hook(
+        &deps.storage,
+        &deps.api,
+        &mock_info(token, &[]),
+        receive_msg(&hook),
+    )
+    .unwrap_err();
+
+    // Then
+    assert!(matches!(
+        err,
+        ContractError::UnexpectedCw20Token { token: t, .. } if t == token
+    ));
+}
+
+#[test_case(StrategyStatus::DepositsPaused, StrategyCw20HookMsg::Deposit { recipient: None } => false; "deposit while deposits paused")]
+#[test_case(StrategyStatus::DepositsPaused, StrategyCw20HookMsg::Redeem { recipient: None } => true; "redeem while deposits paused")]
+#[test_case(StrategyStatus::WithdrawalsPaused, StrategyCw20HookMsg::Redeem { recipient: None } => false; "redeem while withdrawals paused")]
+#[test_case(StrategyStatus::Deprecated, StrategyCw20HookMsg::ZapIn { recipient: None, min_shares: None } => false; "zap in while deprecated")]
+#[test_case(StrategyStatus::Emergency, StrategyCw20HookMsg::EmergencyRedeem { recipient: None } => true; "emergency redeem")]
+fn test_receive_follows_strategy_status(status: StrategyStatus, hook: StrategyCw20HookMsg) -> bool {
+    // Given
+    let mut deps = setup();
+    STRATEGY_STATUS.save(&mut deps.storage, &status).unwrap();
+    let msg: BaseStrategyExecuteMsg<()> = BaseStrategyExecuteMsg::Receive(receive_msg(&hook));
+
+    // When
+    assert_base_execute_allowed(&deps.storage, &msg).is_ok()
+}
+
+#[test]
+fn test_parse_native_deposit() {
+    // Given
+    let mut deps = setup();
+    let uosmo = AssetInfo::NativeToken {
+        denom: "uosmo".to_string(),
+    };
+    BASE_TOKEN.save(&mut deps.storage, &uosmo).unwrap();
+    let deposit = |funds: &[Coin]| {
+        parse_native_deposit(
+            &deps.storage,
+            &deps.api,
+            &mock_info("user", funds),
+            Uint128::new(100),
+            None,
+        )
+    };
+
+    // When
+    let exact = deposit(&[Coin::new(100, "uosmo")]);
+    let extra = deposit(&[Coin::new(100, "uosmo"), Coin::new(5, "uatom")]);
+
+    // Then
+    assert_eq!(
+        exact,
+        Ok(ReceiveAction::Deposit {
+            recipient: Addr::unchecked("user"),
+            amount: Uint128::new(100),
+        })
+    );
+    assert_eq!(
+        extra,
+        Err(ContractError::UnexpectedFunds {
+            denom: "uatom".to_string(),
+            amount: Uint128::new(5),
+        })
+    );
+}
+
+#[test]
+fn test_parse_native_deposit_rejects_cw20_base_token() {
+    // Given
+    let deps = setup();
+
+    // When
+    let err = parse_native_deposit(
+        &deps.storage,
+        &deps.api,
+        &mock_info("user", &[]),
+        Uint128::new(100),
+        None,
+    )
+    .unwrap_err();
+
+    // Then
+    assert_eq!(
+        err,
+        ContractError::Cw20HookRequired {
+            asset: "lp".to_string()
+        }
+    );
+}
+
+#[test_case(&[Coin::new(100, "factory/strategy/shares")] => Ok(Uint128::new(100)); "shares only")]
+#[test_case(&[] => Err(ContractError::InvalidZeroAmount {}); "no shares")]
+#[test_case(&[Coin::new(100, "factory/strategy/shares"), Coin::new(5, "uatom")] => Err(ContractError::UnexpectedFunds { denom: "uatom".to_string(), amount: Uint128::new(5) }); "extra funds")]
+fn test_parse_native_redeem(funds: &[Coin]) -> Result<Uint128, ContractError> {
+    // Given
+    let mut deps = setup();
+    STRATEGY_TOKEN
+        .save(
+            &mut deps.storage,
+            &AssetInfo::NativeToken {
+                denom: "factory/strategy/shares".to_string(),
+            },
+        )
+        .unwrap();
+
+    // When
+    let action = parse_native_redeem(&deps.storage, &deps.api, &mock_info("user", funds), None)?;
+
+    // Then
+    match action {
+        ReceiveAction::Redeem {
+            owner,
+            recipient,
+            shares,
+        } => {
+            assert_eq!(owner, Addr::unchecked("user"));
+            assert_eq!(recipient, owner);
+            Ok(shares)
+        }
+        action => panic!("unexpected action {:?}", action),
+    }
+}
+
+#[test]
+fn test_parse_native_redeem_rejects_cw20_shares() {
+    // Given
+    let deps = setup();
+
+    // When
+    let err = parse_native_redeem(
+        &deps.storage,
+        &deps.api,
+        &mock_info("user", &[Coin::new(100, "shares")]),
+        None,
+    )
+    .unwrap_err();
+
+    // Then
+    assert_eq!(
+        err,
+        ContractError::Cw20HookRequired {
+            asset: "shares".to_string()
+        }
+    );
+}
+
+#[test_case("uatom", &[Coin::new(100, "uatom")] => Ok(()); "exact funds")]
+#[test_case("uion", &[Coin::new(100, "uion")] => Err(ContractError::UnsupportedZapInAsset { asset: "uion".to_string() }); "unsupported asset")]
+#[test_case("uatom", &[] => Err(ContractError::MissingFunds { denom: "uatom".to_string(), expected: Uint128::new(100) }); "missing funds")]
+#[test_case("uatom", &[Coin::new(100, "uatom"), Coin::new(100, "uatom")] => Err(ContractError::DuplicateDenom { denom: "uatom".to_string() }); "duplicate coins")]
+fn test_parse_native_zap_in(denom: &str, funds: &[Coin]) -> Result<(), ContractError> {
+    // Given
+    let mut deps = setup();
+    ZAP_IN_ASSETS
+        .save(
+            &mut deps.storage,
+            &vec![AssetInfo::NativeToken {
+                denom: "uatom".to_string(),
+            }],
+        )
+        .unwrap();
+    let offer = AssetInfo::NativeToken {
+        denom: denom.to_string(),
+    }
+    .to_asset(Uint128::new(100));
+
+    // When
+    parse_native_zap_in(
+        &deps.storage,
+        &deps.api,
+        &mock_info("user", funds),
+        offer,
+        None,
+        None,
+    )
+    .map(|_| ())
+}
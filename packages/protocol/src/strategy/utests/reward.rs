@@ -0,0 +1,373 @@
+use crate::error::ContractError;
+use crate::strategy::reward::{
+    checkpoint_share_transfer, checkpoint_user_rewards, claim_rewards, claimable_rewards,
+    distribute_rewards,
+};
+use crate::strategy::share_token::{burn_shares, mint_shares};
+use crate::strategy::state::STRATEGY_TOKEN;
+use crate::strategy_token::StrategyTokenHookMsg;
+use crate::utests::fixtures::native;
+use apollo_asset::asset::AssetInfo;
+use cosmwasm_std::testing::{mock_dependencies, mock_env, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{
+    to_binary, Addr, BankMsg, Coin, ContractResult, CosmosMsg, OwnedDeps, QuerierWrapper,
+    SystemResult, Uint128, WasmQuery,
+};
+use cw20::BalanceResponse;
+
+fn shares() -> Addr {
+    Addr::unchecked("shares")
+}
+
+fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+    let mut deps = mock_dependencies();
+    STRATEGY_TOKEN
+        .save(
+            deps.as_mut().storage,
+            &AssetInfo::Token {
+                contract_addr: shares(),
+            },
+        )
+        .unwrap();
+    deps
+}
+
+/// Makes the cw20 strategy token report `balance` shares for every address.
+fn set_share_balance(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, balance: u128) {
+    deps.querier.update_wasm(move |query| match query {
+        WasmQuery::Smart { .. } => SystemResult::Ok(ContractResult::Ok(
+            to_binary(&BalanceResponse {
+                balance: Uint128::new(balance),
+            })
+            .unwrap(),
+        )),
+        query => panic!("unexpected query {:?}", query),
+    });
+}
+
+#[test]
+fn test_rewards_split_by_shares_across_tokens() {
+    // Given
+    let mut deps = mock_dependencies();
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    checkpoint_user_rewards(deps.as_mut().storage, &alice, Uint128::zero()).unwrap();
+    checkpoint_user_rewards(deps.as_mut().storage, &bob, Uint128::zero()).unwrap();
+
+    // When
+    distribute_rewards(
+        deps.as_mut().storage,
+        &native("uosmo", 100),
+        Uint128::new(100),
+    )
+    .unwrap();
+    distribute_rewards(
+        deps.as_mut().storage,
+        &native("uion", 10),
+        Uint128::new(100),
+    )
+    .unwrap();
+
+    // Then
+    assert_eq!(
+        claimable_rewards(deps.as_ref().storage, &alice, Uint128::new(75)).unwrap(),
+        vec![native("uosmo", 75), native("uion", 7)]
+    );
+    assert_eq!(
+        claimable_rewards(deps.as_ref().storage, &bob, Uint128::new(25)).unwrap(),
+        vec![native("uosmo", 25), native("uion", 2)]
+    );
+}
+
+#[test]
+fn test_checkpoint_excludes_rewards_before_deposit() {
+    // Given
+    let mut deps = mock_dependencies();
+    let alice = Addr::unchecked("alice");
+    distribute_rewards(
+        deps.as_mut().storage,
+        &native("uosmo", 100),
+        Uint128::new(100),
+    )
+    .unwrap();
+
+    // When alice deposits 100 shares after the first distribution
+    checkpoint_user_rewards(deps.as_mut().storage, &alice, Uint128::zero()).unwrap();
+    distribute_rewards(
+        deps.as_mut().storage,
+        &native("uosmo", 100),
+        Uint128::new(200),
+    )
+    .unwrap();
+
+    // Then
+    assert_eq!(
+        claimable_rewards(deps.as_ref().storage, &alice, Uint128::new(100)).unwrap(),
+        vec![native("uosmo", 50)]
+    );
+}
+
+#[test]
+fn test_share_transfer_keeps_accrued_rewards() {
+    // Given
+    let mut deps = setup();
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    checkpoint_user_rewards(deps.as_mut().storage, &alice, Uint128::zero()).unwrap();
+    distribute_rewards(
+        deps.as_mut().storage,
+        &native("uosmo", 100),
+        Uint128::new(100),
+    )
+    .unwrap();
+
+    // When alice sends all her shares to bob
+    let transfer = StrategyTokenHookMsg::SharesTransferred {
+        from: alice.to_string(),
+        to: bob.to_string(),
+        amount: Uint128::new(100),
+        from_balance: Uint128::new(100),
+        to_balance: Uint128::zero(),
+    };
+    checkpoint_share_transfer(deps.as_mut().storage, &shares(), &transfer).unwrap();
+    distribute_rewards(
+        deps.as_mut().storage,
+        &native("uosmo", 50),
+        Uint128::new(100),
+    )
+    .unwrap();
+
+    // Then
+    assert_eq!(
+        claimable_rewards(deps.as_ref().storage, &alice, Uint128::zero()).unwrap(),
+        vec![native("uosmo", 100)]
+    );
+    assert_eq!(
+        claimable_rewards(deps.as_ref().storage, &bob, Uint128::new(100)).unwrap(),
+        vec![native("uosmo", 50)]
+    );
+}
+
+#[test]
+fn test_fresh_address_does_not_claim_past_rewards() {
+    // Given
+    let mut deps = setup();
+    let alice = Addr::unchecked("alice");
+    let fresh = Addr::unchecked("fresh");
+    checkpoint_user_rewards(deps.as_mut().storage, &alice, Uint128::zero()).unwrap();
+    distribute_rewards(
+        deps.as_mut().storage,
+        &native("uosmo", 100),
+        Uint128::new(100),
+    )
+    .unwrap();
+
+    // When alice moves her shares to a fresh address which claims right away
+    let transfer = StrategyTokenHookMsg::SharesTransferred {
+        from: alice.to_string(),
+        to: fresh.to_string(),
+        amount: Uint128::new(100),
+        from_balance: Uint128::new(100),
+        to_balance: Uint128::zero(),
+    };
+    checkpoint_share_transfer(deps.as_mut().storage, &shares(), &transfer).unwrap();
+    let response = claim_rewards(deps.as_mut().storage, &fresh, Uint128::new(100), &fresh).unwrap();
+
+    // Then
+    assert!(response.messages.is_empty());
+    assert_eq!(
+        claimable_rewards(deps.as_ref().storage, &alice, Uint128::zero()).unwrap(),
+        vec![native("uosmo", 100)]
+    );
+}
+
+#[test]
+fn test_unchecked_holder_starts_at_current_index() {
+    // Given shares that reached an address without any checkpoint
+    let mut deps = mock_dependencies();
+    let fresh = Addr::unchecked("fresh");
+    distribute_rewards(
+        deps.as_mut().storage,
+        &native("uosmo", 100),
+        Uint128::new(100),
+    )
+    .unwrap();
+
+    // When
+    let claimable = claimable_rewards(deps.as_ref().storage, &fresh, Uint128::new(100)).unwrap();
+
+    // Then
+    assert!(claimable.is_empty());
+}
+
+#[test]
+fn test_claim_rewards() {
+    // Given
+    let mut deps = mock_dependencies();
+    let alice = Addr::unchecked("alice");
+    checkpoint_user_rewards(deps.as_mut().storage, &alice, Uint128::zero()).unwrap();
+    distribute_rewards(
+        deps.as_mut().storage,
+        &native("uosmo", 100),
+        Uint128::new(100),
+    )
+    .unwrap();
+
+    // When
+    let response = claim_rewards(deps.as_mut().storage, &alice, Uint128::new(100), &alice).unwrap();
+
+    // Then
+    assert_eq!(
+        response.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "alice".to_string(),
+            amount: vec![Coin::new(100, "uosmo")],
+        })
+    );
+    assert!(
+        claimable_rewards(deps.as_ref().storage, &alice, Uint128::new(100))
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_distribute_without_shares_fails() {
+    let mut deps = mock_dependencies();
+
+    let err = distribute_rewards(
+        deps.as_mut().storage,
+        &native("uosmo", 100),
+        Uint128::zero(),
+    )
+    .unwrap_err();
+
+    assert_eq!(err, ContractError::NoSharesToDistributeRewards {});
+}
+
+#[test]
+fn test_distribute_on_native_shares_fails() {
+    // Given
+    let mut deps = mock_dependencies();
+    STRATEGY_TOKEN
+        .save(
+            deps.as_mut().storage,
+            &AssetInfo::NativeToken {
+                denom: "factory/strategy/shares".to_string(),
+            },
+        )
+        .unwrap();
+
+    // When
+    let err = distribute_rewards(
+        deps.as_mut().storage,
+        &native("uosmo", 100),
+        Uint128::new(1_000),
+    )
+    .unwrap_err();
+
+    // Then
+    assert_eq!(err, ContractError::NativeShareRewards {});
+}
+
+#[test]
+fn test_spoofed_share_transfer_is_rejected() {
+    // Given
+    let mut deps = setup();
+    let transfer = StrategyTokenHookMsg::SharesTransferred {
+        from: "alice".to_string(),
+        to: "mallory".to_string(),
+        amount: Uint128::new(100),
+        from_balance: Uint128::zero(),
+        to_balance: Uint128::new(1_000_000),
+    };
+
+    // When
+    let err = checkpoint_share_transfer(
+        deps.as_mut().storage,
+        &Addr::unchecked("mallory"),
+        &transfer,
+    )
+    .unwrap_err();
+
+    // Then
+    assert_eq!(err, ContractError::Unauthorized {});
+}
+
+#[test]
+fn test_deposit_does_not_earn_past_rewards() {
+    // Given alice holding 100 shares with 100 uosmo of rewards accrued
+    let mut deps = setup();
+    let env = mock_env();
+    let alice = Addr::unchecked("alice");
+    checkpoint_user_rewards(deps.as_mut().storage, &alice, Uint128::new(100)).unwrap();
+    distribute_rewards(
+        deps.as_mut().storage,
+        &native("uosmo", 100),
+        Uint128::new(100),
+    )
+    .unwrap();
+    set_share_balance(&mut deps, 100);
+
+    // When she deposits for 100 more shares before the next distribution
+    mint_shares(
+        &QuerierWrapper::new(&deps.querier),
+        &mut deps.storage,
+        &env,
+        &alice,
+        Uint128::new(100),
+    )
+    .unwrap();
+    distribute_rewards(
+        deps.as_mut().storage,
+        &native("uosmo", 200),
+        Uint128::new(200),
+    )
+    .unwrap();
+    let response = claim_rewards(deps.as_mut().storage, &alice, Uint128::new(200), &alice).unwrap();
+
+    // Then
+    assert_eq!(
+        response.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "alice".to_string(),
+            amount: vec![Coin::new(300, "uosmo")],
+        })
+    );
+}
+
+#[test]
+fn test_redeem_keeps_accrued_rewards() {
+    // Given alice holding 100 shares with 100 uosmo of rewards accrued
+    let mut deps = setup();
+    let env = mock_env();
+    let alice = Addr::unchecked("alice");
+    checkpoint_user_rewards(deps.as_mut().storage, &alice, Uint128::new(100)).unwrap();
+    distribute_rewards(
+        deps.as_mut().storage,
+        &native("uosmo", 100),
+        Uint128::new(100),
+    )
+    .unwrap();
+
+    // When all her shares are sent to the strategy and burned
+    set_share_balance(&mut deps, 0);
+    burn_shares(
+        &QuerierWrapper::new(&deps.querier),
+        &mut deps.storage,
+        &env,
+        &alice,
+        Uint128::new(100),
+    )
+    .unwrap();
+    let response = claim_rewards(deps.as_mut().storage, &alice, Uint128::zero(), &alice).unwrap();
+
+    // Then
+    assert_eq!(
+        response.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "alice".to_string(),
+            amount: vec![Coin::new(100, "uosmo")],
+        })
+    );
+}
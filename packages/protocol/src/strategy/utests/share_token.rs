@@ -0,0 +1,93 @@
+use crate::strategy::share_token::{
+    burn_shares, encode_msg_create_denom, encode_msg_mint_or_burn, init_share_token, mint_shares,
+    query_share_supply, ShareTokenInit,
+};
+use crate::strategy::state::STRATEGY_TOKEN;
+use apollo_asset::asset::AssetInfo;
+use cosmwasm_std::testing::{mock_dependencies, mock_env};
+use cosmwasm_std::{Addr, BankMsg, Coin, CosmosMsg, QuerierWrapper, Uint128};
+
+#[test]
+fn test_encode_msg_create_denom() {
+    let encoded = encode_msg_create_denom("abc", "xy");
+
+    assert_eq!(
+        encoded.as_slice(),
+        &[0x0a, 3, b'a', b'b', b'c', 0x12, 2, b'x', b'y']
+    );
+}
+
+#[test]
+fn test_encode_msg_mint_or_burn() {
+    let encoded = encode_msg_mint_or_burn("s", &Coin::new(10, "d"));
+
+    assert_eq!(
+        encoded.as_slice(),
+        &[0x0a, 1, b's', 0x12, 7, 0x0a, 1, b'd', 0x12, 2, b'1', b'0']
+    );
+}
+
+#[test]
+fn test_token_factory_supply_accounting() {
+    // Given
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let init = ShareTokenInit::TokenFactory {
+        subdenom: "shares".to_string(),
+    };
+    init_share_token(deps.as_mut().storage, &env, init, "factory".to_string(), 1).unwrap();
+    let denom = format!("factory/{}/shares", env.contract.address);
+    assert_eq!(
+        STRATEGY_TOKEN.load(deps.as_ref().storage).unwrap(),
+        AssetInfo::NativeToken {
+            denom: denom.clone()
+        }
+    );
+
+    // When
+    let msgs = mint_shares(
+        &QuerierWrapper::new(&deps.querier),
+        &mut deps.storage,
+        &env,
+        &Addr::unchecked("user"),
+        Uint128::new(100),
+    )
+    .unwrap();
+    burn_shares(
+        &QuerierWrapper::new(&deps.querier),
+        &mut deps.storage,
+        &env,
+        &Addr::unchecked("user"),
+        Uint128::new(40),
+    )
+    .unwrap();
+
+    // Then
+    assert_eq!(
+        msgs[1],
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "user".to_string(),
+            amount: vec![Coin::new(100, denom)],
+        })
+    );
+    assert_eq!(query_share_supply(deps.as_ref()).unwrap(), Uint128::new(60));
+}
+
+#[test]
+fn test_burn_more_than_supply_fails() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let init = ShareTokenInit::TokenFactory {
+        subdenom: "shares".to_string(),
+    };
+    init_share_token(deps.as_mut().storage, &env, init, "factory".to_string(), 1).unwrap();
+
+    assert!(burn_shares(
+        &QuerierWrapper::new(&deps.querier),
+        &mut deps.storage,
+        &env,
+        &Addr::unchecked("user"),
+        Uint128::new(1),
+    )
+    .is_err());
+}
@@ -0,0 +1,218 @@
+use crate::error::ContractError;
+use crate::strategy::msg::ExecuteMsg;
+use crate::strategy::share_token::{init_share_token, mint_shares, ShareTokenInit};
+use crate::strategy::state::{BASE_TOKEN, FACTORY, STAKING_ADAPTOR};
+use crate::strategy::status::{
+    assert_action_allowed, assert_base_execute_allowed, emergency_redeem, load_status,
+    record_emergency_unwind, update_guardian, update_status, StatusRole, StrategyAction,
+    StrategyStatus, EMERGENCY_UNWIND, STRATEGY_STATUS,
+};
+use apollo_asset::asset::AssetInfo;
+use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env};
+use cosmwasm_std::{Addr, BankMsg, Coin, CosmosMsg, QuerierWrapper, Uint128};
+use test_case::test_case;
+
+use StrategyStatus::*;
+
+#[test_case(Active, DepositsPaused, StatusRole::Guardian => true; "guardian pauses deposits")]
+#[test_case(DepositsPaused, Paused, StatusRole::Guardian => true; "guardian pauses everything")]
+#[test_case(DepositsPaused, Active, StatusRole::Guardian => false; "guardian cannot unpause")]
+#[test_case(DepositsPaused, Active, StatusRole::Owner => true; "owner unpauses")]
+#[test_case(Active, Deprecated, StatusRole::Guardian => false; "guardian cannot deprecate")]
+#[test_case(Active, Deprecated, StatusRole::Owner => true; "owner deprecates")]
+#[test_case(Deprecated, Active, StatusRole::Owner => false; "deprecation is final")]
+#[test_case(Active, Emergency, StatusRole::Guardian => false; "guardian cannot trigger emergency")]
+#[test_case(Paused, Emergency, StatusRole::Guardian => false; "guardian cannot trigger emergency once paused")]
+#[test_case(Deprecated, Emergency, StatusRole::Owner => true; "owner triggers emergency")]
+#[test_case(Emergency, Active, StatusRole::Owner => false; "emergency is terminal")]
+#[test_case(Paused, Paused, StatusRole::Owner => false; "same status")]
+fn test_can_transition_to(from: StrategyStatus, to: StrategyStatus, role: StatusRole) -> bool {
+    from.can_transition_to(to, role)
+}
+
+#[test_case(Active, StrategyAction::Deposit => true; "active deposit")]
+#[test_case(Active, StrategyAction::EmergencyRedeem => false; "active emergency redeem")]
+#[test_case(DepositsPaused, StrategyAction::Withdraw => true; "deposits paused withdraw")]
+#[test_case(WithdrawalsPaused, StrategyAction::Withdraw => false; "withdrawals paused withdraw")]
+#[test_case(Paused, StrategyAction::Execute => false; "paused execute")]
+#[test_case(Deprecated, StrategyAction::Deposit => false; "deprecated deposit")]
+#[test_case(Deprecated, StrategyAction::Withdraw => true; "deprecated withdraw")]
+#[test_case(Emergency, StrategyAction::EmergencyRedeem => true; "emergency redeem")]
+fn test_allows(status: StrategyStatus, action: StrategyAction) -> bool {
+    status.allows(action)
+}
+
+#[test_case(Active; "active")]
+#[test_case(DepositsPaused; "deposits paused")]
+#[test_case(WithdrawalsPaused; "withdrawals paused")]
+#[test_case(Paused; "paused")]
+#[test_case(Deprecated; "deprecated")]
+#[test_case(Emergency; "emergency")]
+fn test_claim_unbonded_allowed_in_every_status(status: StrategyStatus) {
+    // Given
+    let mut deps = mock_dependencies_with_balance(&[]);
+    STRATEGY_STATUS
+        .save(deps.as_mut().storage, &status)
+        .unwrap();
+
+    // When
+    let res = assert_base_execute_allowed(
+        deps.as_ref().storage,
+        &ExecuteMsg::ClaimUnbonded { recipient: None },
+    );
+
+    // Then
+    assert_eq!(res, Ok(()));
+}
+
+#[test_case(Active; "active")]
+#[test_case(WithdrawalsPaused; "withdrawals paused")]
+#[test_case(Paused; "paused")]
+#[test_case(Emergency; "emergency")]
+fn test_claim_rewards_allowed_in_every_status(status: StrategyStatus) {
+    // Given
+    let mut deps = mock_dependencies_with_balance(&[]);
+    STRATEGY_STATUS
+        .save(deps.as_mut().storage, &status)
+        .unwrap();
+
+    // When
+    let res = assert_base_execute_allowed(
+        deps.as_ref().storage,
+        &ExecuteMsg::Claim { recipient: None },
+    );
+
+    // Then
+    assert_eq!(res, Ok(()));
+}
+
+#[test_case(DepositsPaused => true; "deposits paused")]
+#[test_case(WithdrawalsPaused => false; "withdrawals paused")]
+#[test_case(Deprecated => true; "deprecated")]
+#[test_case(Emergency => false; "emergency")]
+fn test_native_redeem_follows_strategy_status(status: StrategyStatus) -> bool {
+    // Given
+    let mut deps = mock_dependencies_with_balance(&[]);
+    STRATEGY_STATUS
+        .save(deps.as_mut().storage, &status)
+        .unwrap();
+
+    // When
+    assert_base_execute_allowed(
+        deps.as_ref().storage,
+        &ExecuteMsg::Redeem { recipient: None },
+    )
+    .is_ok()
+}
+
+#[test]
+fn test_update_status_permissions() {
+    // Given
+    let mut deps = mock_dependencies_with_balance(&[]);
+    FACTORY
+        .save(deps.as_mut().storage, &Addr::unchecked("factory"))
+        .unwrap();
+    let guardian = Addr::unchecked("guardian");
+
+    // When
+    let not_guardian_yet = update_status(deps.as_mut().storage, &guardian, Paused);
+    update_guardian(
+        deps.as_mut().storage,
+        &Addr::unchecked("factory"),
+        Some(guardian.clone()),
+    )
+    .unwrap();
+    update_status(deps.as_mut().storage, &guardian, Paused).unwrap();
+    let unpause = update_status(deps.as_mut().storage, &guardian, Active);
+
+    // Then
+    assert_eq!(not_guardian_yet, Err(ContractError::Unauthorized {}));
+    assert_eq!(
+        unpause,
+        Err(ContractError::InvalidStatusTransition {
+            from: "paused".to_string(),
+            to: "active".to_string(),
+        })
+    );
+    assert_eq!(load_status(deps.as_ref().storage).unwrap(), Paused);
+    assert_eq!(
+        assert_action_allowed(deps.as_ref().storage, StrategyAction::Deposit),
+        Err(ContractError::ActionNotAllowed {
+            action: "deposit".to_string(),
+            status: "paused".to_string(),
+        })
+    );
+}
+
+#[test]
+fn test_emergency_redeem_is_pro_rata() {
+    // Given
+    let mut deps = mock_dependencies_with_balance(&[Coin::new(1_000, "uosmo")]);
+    let env = mock_env();
+    let factory = Addr::unchecked("factory");
+    FACTORY.save(deps.as_mut().storage, &factory).unwrap();
+    STAKING_ADAPTOR
+        .save(deps.as_mut().storage, &Addr::unchecked("staking_adaptor"))
+        .unwrap();
+    BASE_TOKEN
+        .save(
+            deps.as_mut().storage,
+            &AssetInfo::NativeToken {
+                denom: "uosmo".to_string(),
+            },
+        )
+        .unwrap();
+    let init = ShareTokenInit::TokenFactory {
+        subdenom: "shares".to_string(),
+    };
+    init_share_token(deps.as_mut().storage, &env, init, "factory".to_string(), 1).unwrap();
+    mint_shares(
+        &QuerierWrapper::new(&deps.querier),
+        &mut deps.storage,
+        &env,
+        &Addr::unchecked("user"),
+        Uint128::new(400),
+    )
+    .unwrap();
+
+    // When
+    let res = update_status(deps.as_mut().storage, &factory, Emergency).unwrap();
+    record_emergency_unwind(deps.as_mut(), &env).unwrap();
+    let first = emergency_redeem(
+        &QuerierWrapper::new(&deps.querier),
+        &mut deps.storage,
+        &env,
+        &Addr::unchecked("user"),
+        &Addr::unchecked("alice"),
+        Uint128::new(100),
+    )
+    .unwrap();
+    let second = emergency_redeem(
+        &QuerierWrapper::new(&deps.querier),
+        &mut deps.storage,
+        &env,
+        &Addr::unchecked("user"),
+        &Addr::unchecked("bob"),
+        Uint128::new(100),
+    )
+    .unwrap();
+
+    // Then
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(
+        EMERGENCY_UNWIND
+            .load(deps.as_ref().storage)
+            .unwrap()
+            .total_shares,
+        Uint128::new(400)
+    );
+    for (res, recipient) in [(first, "alice"), (second, "bob")] {
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: vec![Coin::new(250, "uosmo")],
+            })
+        );
+    }
+}
@@ -0,0 +1,173 @@
+use crate::error::ContractError;
+use crate::strategy::share_token::{init_share_token, mint_shares, ShareTokenInit};
+use crate::strategy::state::{BASE_TOKEN, STAKING_ADAPTOR};
+use crate::strategy::status::{record_emergency_unwind, EMERGENCY_UNWIND};
+use crate::strategy::unbonding::{
+    claim_unbonded, query_claims, request_withdrawal, start_unbonding, UnbondingConfig,
+    UNBONDING_CONFIG,
+};
+use apollo_asset::asset::AssetInfo;
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR,
+};
+use cosmwasm_std::{Addr, BankMsg, Coin, CosmosMsg, Env, OwnedDeps, QuerierWrapper, Uint128};
+
+const PERIOD: u64 = 1_000;
+
+fn setup(
+    max_concurrent_unbondings: Option<u32>,
+) -> (OwnedDeps<MockStorage, MockApi, MockQuerier>, Env) {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    UNBONDING_CONFIG
+        .save(
+            &mut deps.storage,
+            &UnbondingConfig {
+                unbonding_period: PERIOD,
+                max_concurrent_unbondings,
+            },
+        )
+        .unwrap();
+    STAKING_ADAPTOR
+        .save(&mut deps.storage, &Addr::unchecked("staking_adaptor"))
+        .unwrap();
+    BASE_TOKEN
+        .save(
+            &mut deps.storage,
+            &AssetInfo::NativeToken {
+                denom: "uosmo".to_string(),
+            },
+        )
+        .unwrap();
+    let init = ShareTokenInit::TokenFactory {
+        subdenom: "shares".to_string(),
+    };
+    init_share_token(&mut deps.storage, &env, init, "factory".to_string(), 1).unwrap();
+    mint_shares(
+        &QuerierWrapper::new(&deps.querier),
+        &mut deps.storage,
+        &env,
+        &Addr::unchecked("user"),
+        Uint128::new(1_000),
+    )
+    .unwrap();
+    (deps, env)
+}
+
+#[test]
+fn test_withdrawal_is_claimable_after_unbonding() {
+    // Given
+    let (mut deps, mut env) = setup(None);
+    let user = Addr::unchecked("user");
+
+    // When
+    let res = request_withdrawal(
+        &QuerierWrapper::new(&deps.querier),
+        &mut deps.storage,
+        &env,
+        &user,
+        Uint128::new(100),
+        Uint128::new(150),
+    )
+    .unwrap();
+    let early = claim_unbonded(&mut deps.storage, &env, &user, &user);
+    env.block.time = env.block.time.plus_seconds(PERIOD);
+    let claimed = claim_unbonded(&mut deps.storage, &env, &user, &user).unwrap();
+
+    // Then
+    // burn and unbond
+    assert_eq!(res.messages.len(), 2);
+    assert_eq!(early, Err(ContractError::NothingToClaim {}));
+    assert_eq!(
+        claimed.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "user".to_string(),
+            amount: vec![Coin::new(150, "uosmo")],
+        })
+    );
+    assert!(query_claims(&deps.storage, &user)
+        .unwrap()
+        .claims
+        .is_empty());
+}
+
+#[test]
+fn test_requests_wait_for_a_free_unbonding_slot() {
+    // Given
+    let (mut deps, mut env) = setup(Some(1));
+    let user = Addr::unchecked("user");
+
+    // When
+    for _ in 0..3 {
+        request_withdrawal(
+            &QuerierWrapper::new(&deps.querier),
+            &mut deps.storage,
+            &env,
+            &user,
+            Uint128::new(10),
+            Uint128::new(10),
+        )
+        .unwrap();
+    }
+    let no_slot = start_unbonding(&mut deps.storage, &env);
+    env.block.time = env.block.time.plus_seconds(PERIOD);
+    let started = start_unbonding(&mut deps.storage, &env).unwrap();
+
+    // Then
+    assert_eq!(no_slot, Err(ContractError::NoUnbondingToStart {}));
+    assert_eq!(started.messages.len(), 1);
+    let claims = query_claims(&deps.storage, &user).unwrap().claims;
+    // the first request was unbonded alone, the two others were batched together
+    assert_eq!(claims.len(), 2);
+    assert_eq!(claims[0].amount, Uint128::new(10));
+    assert_eq!(claims[1].amount, Uint128::new(20));
+    assert_eq!(
+        claims[1].release_at,
+        Some(env.block.time.seconds() + PERIOD)
+    );
+}
+
+#[test]
+fn test_emergency_unwind_leaves_unbonding_claims_out() {
+    // Given a released withdrawal of 150 and one of 50 still unbonding
+    let (mut deps, mut env) = setup(None);
+    let user = Addr::unchecked("user");
+    request_withdrawal(
+        &QuerierWrapper::new(&deps.querier),
+        &mut deps.storage,
+        &env,
+        &user,
+        Uint128::new(100),
+        Uint128::new(150),
+    )
+    .unwrap();
+    env.block.time = env.block.time.plus_seconds(PERIOD);
+    request_withdrawal(
+        &QuerierWrapper::new(&deps.querier),
+        &mut deps.storage,
+        &env,
+        &user,
+        Uint128::new(40),
+        Uint128::new(50),
+    )
+    .unwrap();
+    // the unwind brought the staked funds back next to the released 150
+    deps.querier
+        .update_balance(MOCK_CONTRACT_ADDR, vec![Coin::new(1_000, "uosmo")]);
+
+    // When
+    record_emergency_unwind(deps.as_mut(), &env).unwrap();
+    let claimed = claim_unbonded(&mut deps.storage, &env, &user, &user).unwrap();
+
+    // Then
+    let unwind = EMERGENCY_UNWIND.load(&deps.storage).unwrap();
+    assert_eq!(unwind.total_base_amount, Uint128::new(850));
+    assert_eq!(unwind.total_shares, Uint128::new(860));
+    assert_eq!(
+        claimed.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "user".to_string(),
+            amount: vec![Coin::new(150, "uosmo")],
+        })
+    );
+}
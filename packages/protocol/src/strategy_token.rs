@@ -1,7 +1,23 @@
-use cw20_base::msg::InstantiateMsg as Cw20InstantiateMsg;
+use cosmwasm_std::{
+    to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, SubMsg, Uint128,
+    WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, MinterResponse};
+use cw20_base::msg::{InstantiateMsg as Cw20InstantiateMsg, QueryMsg as Cw20QueryMsg};
+use cw20_base::state::BALANCES;
+use cw_storage_plus::Item;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::{
+    error::ContractError, strategy::msg::ExecuteMsg as StrategyExecuteMsg,
+    utils::only_allow_human_address,
+};
+
+pub const STRATEGY: Item<Addr> = Item::new("strategy");
+pub const FACTORY: Item<Addr> = Item::new("factory");
+pub const STRATEGY_ID: Item<u64> = Item::new("strategy_id");
+
 #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub struct InstantiateMsg {
     pub base_init_msg: Cw20InstantiateMsg,
@@ -9,7 +25,159 @@ pub struct InstantiateMsg {
     pub strategy_id: u64,
 }
 
+pub type ExecuteMsg = Cw20ExecuteMsg;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(untagged)]
+pub enum QueryMsg {
+    StrategyToken(StrategyTokenQueryMsg),
+    Cw20(Cw20QueryMsg),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[schemars(deny_unknown_fields)]
+pub enum StrategyTokenQueryMsg {
+    StrategyId {},
+    Factory {},
+    Strategy {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct StrategyIdResponse {
+    pub strategy_id: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct AddressResponse {
+    pub address: Addr,
+}
+
+/// Sent to the owning strategy after every transfer of strategy tokens. Balances are the
+/// ones before the transfer, so the strategy can checkpoint rewards for both accounts.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[schemars(deny_unknown_fields)]
+pub enum StrategyTokenHookMsg {
+    SharesTransferred {
+        from: String,
+        to: String,
+        amount: Uint128,
+        from_balance: Uint128,
+        to_balance: Uint128,
+    },
+}
+
 /// Allow Migration
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 #[schemars(deny_unknown_fields)]
 pub struct MigrateMsg {}
+
+/// Instantiates the cw20 with the instantiating strategy as the only minter.
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    STRATEGY.save(deps.storage, &info.sender)?;
+    FACTORY.save(deps.storage, &deps.api.addr_validate(&msg.apollo_factory)?)?;
+    STRATEGY_ID.save(deps.storage, &msg.strategy_id)?;
+
+    let mut base_init_msg = msg.base_init_msg;
+    base_init_msg.mint = Some(MinterResponse {
+        minter: info.sender.to_string(),
+        cap: base_init_msg.mint.and_then(|m| m.cap),
+    });
+
+    Ok(cw20_base::contract::instantiate(
+        deps,
+        env,
+        info,
+        base_init_msg,
+    )?)
+}
+
+/// Delegates to cw20-base. Minting and burning are restricted to the owning strategy and
+/// transfers notify it with a `StrategyTokenHookMsg`.
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    let strategy = STRATEGY.load(deps.storage)?;
+
+    let transfer = match &msg {
+        ExecuteMsg::Mint { .. } | ExecuteMsg::Burn { .. } | ExecuteMsg::BurnFrom { .. } => {
+            only_allow_human_address(&info, &strategy)?;
+            None
+        }
+        ExecuteMsg::Transfer { recipient, amount }
+        | ExecuteMsg::Send {
+            contract: recipient,
+            amount,
+            ..
+        } => Some((info.sender.clone(), recipient, *amount)),
+        ExecuteMsg::TransferFrom {
+            owner,
+            recipient,
+            amount,
+        }
+        | ExecuteMsg::SendFrom {
+            owner,
+            contract: recipient,
+            amount,
+            ..
+        } => Some((deps.api.addr_validate(owner)?, recipient, *amount)),
+        _ => None,
+    };
+
+    let hook = match transfer {
+        Some((from, to, amount)) if !amount.is_zero() => {
+            let to = deps.api.addr_validate(to)?;
+            Some(WasmMsg::Execute {
+                contract_addr: strategy.to_string(),
+                msg: to_binary(&StrategyExecuteMsg::StrategyTokenHook(
+                    StrategyTokenHookMsg::SharesTransferred {
+                        from_balance: BALANCES.may_load(deps.storage, &from)?.unwrap_or_default(),
+                        to_balance: BALANCES.may_load(deps.storage, &to)?.unwrap_or_default(),
+                        from: from.to_string(),
+                        to: to.to_string(),
+                        amount,
+                    },
+                ))?,
+                funds: vec![],
+            })
+        }
+        _ => None,
+    };
+
+    let mut res = cw20_base::contract::execute(deps, env, info, msg)?;
+    // checkpoint first, `Send` callbacks must not run against stale reward indexes
+    if let Some(hook) = hook {
+        res.messages.insert(0, SubMsg::new(hook));
+    }
+    Ok(res)
+}
+
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::StrategyToken(StrategyTokenQueryMsg::StrategyId {}) => {
+            to_binary(&StrategyIdResponse {
+                strategy_id: STRATEGY_ID.load(deps.storage)?,
+            })
+        }
+        QueryMsg::StrategyToken(StrategyTokenQueryMsg::Factory {}) => to_binary(&AddressResponse {
+            address: FACTORY.load(deps.storage)?,
+        }),
+        QueryMsg::StrategyToken(StrategyTokenQueryMsg::Strategy {}) => {
+            to_binary(&AddressResponse {
+                address: STRATEGY.load(deps.storage)?,
+            })
+        }
+        QueryMsg::Cw20(msg) => cw20_base::contract::query(deps, env, msg),
+    }
+}
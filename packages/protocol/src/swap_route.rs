@@ -0,0 +1,157 @@
+use apollo_asset::{
+    asset::{Asset, AssetInfo},
+    pair::{pair_key, PairInfo},
+};
+use cosmwasm_std::{Addr, Order, QuerierWrapper, StdResult, Storage, Uint128};
+use cw_storage_plus::Map;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    dex_adaptor::{query_simulate_route_swap, SwapOperation},
+    error::ContractError,
+};
+
+/// Longest route considered by the route-finder.
+pub const MAX_HOPS: usize = 3;
+
+pub const PAIRS: Map<&[u8], PairInfo> = Map::new("swap_route_pairs");
+
+/// Ordered hops of a multi-hop swap, the pool of each hop being the pair address.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct SwapRoute {
+    pub hops: Vec<SwapOperation>,
+}
+
+impl SwapRoute {
+    /// Checks the route is not empty, that every hop swaps between two different assets and
+    /// that each hop offers what the previous one asked for, without visiting an asset twice.
+    pub fn validate(&self) -> Result<(), ContractError> {
+        let first = self
+            .hops
+            .first()
+            .ok_or(ContractError::InvalidSwapRoute {})?;
+        let mut visited = vec![&first.offer];
+        for (i, hop) in self.hops.iter().enumerate() {
+            if i > 0 && hop.offer != self.hops[i - 1].ask {
+                return Err(ContractError::InvalidSwapRoute {});
+            }
+            if visited.contains(&&hop.ask) {
+                return Err(ContractError::InvalidSwapRoute {});
+            }
+            visited.push(&hop.ask);
+        }
+        Ok(())
+    }
+
+    pub fn offer(&self) -> Option<&AssetInfo> {
+        self.hops.first().map(|hop| &hop.offer)
+    }
+
+    pub fn ask(&self) -> Option<&AssetInfo> {
+        self.hops.last().map(|hop| &hop.ask)
+    }
+}
+
+pub fn register_pair(storage: &mut dyn Storage, pair: &PairInfo) -> StdResult<()> {
+    PAIRS.save(storage, &pair_key(&pair.asset_infos), pair)
+}
+
+pub fn remove_pair(storage: &mut dyn Storage, asset_infos: &[AssetInfo; 2]) {
+    PAIRS.remove(storage, &pair_key(asset_infos))
+}
+
+pub fn get_pair(storage: &dyn Storage, asset_infos: &[AssetInfo; 2]) -> StdResult<PairInfo> {
+    PAIRS.load(storage, &pair_key(asset_infos))
+}
+
+/// Every simple route of at most `max_hops` hops from `offer` to `ask` over the registered
+/// pairs.
+pub fn find_routes(
+    storage: &dyn Storage,
+    offer: &AssetInfo,
+    ask: &AssetInfo,
+    max_hops: usize,
+) -> StdResult<Vec<SwapRoute>> {
+    let pairs = PAIRS
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, pair)| pair))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut routes = vec![];
+    let mut hops = vec![];
+    collect_routes(&pairs, offer, ask, max_hops, &mut hops, &mut routes);
+    Ok(routes)
+}
+
+fn collect_routes(
+    pairs: &[PairInfo],
+    from: &AssetInfo,
+    ask: &AssetInfo,
+    max_hops: usize,
+    hops: &mut Vec<SwapOperation>,
+    routes: &mut Vec<SwapRoute>,
+) {
+    if hops.len() == max_hops {
+        return;
+    }
+    for pair in pairs {
+        let next = match &pair.asset_infos {
+            [a, b] if a == from => b,
+            [a, b] if b == from => a,
+            _ => continue,
+        };
+        // keep routes simple
+        if hops.iter().any(|hop| &hop.offer == next) {
+            continue;
+        }
+
+        hops.push(SwapOperation {
+            pool: pair.contract_addr.to_string(),
+            offer: from.clone(),
+            ask: next.clone(),
+        });
+        if next == ask {
+            routes.push(SwapRoute { hops: hops.clone() });
+        } else {
+            collect_routes(pairs, next, ask, max_hops, hops, routes);
+        }
+        hops.pop();
+    }
+}
+
+/// Route returning the most of `ask` for `offer` according to `simulate`. Routes that fail to
+/// simulate, e.g. through an empty pool, are skipped.
+pub fn find_best_route<F>(
+    storage: &dyn Storage,
+    offer: &Asset,
+    ask: &AssetInfo,
+    max_hops: usize,
+    simulate: F,
+) -> Result<(SwapRoute, Uint128), ContractError>
+where
+    F: Fn(&SwapRoute) -> StdResult<Uint128>,
+{
+    find_routes(storage, &offer.info, ask, max_hops)?
+        .into_iter()
+        .filter_map(|route| simulate(&route).ok().map(|amount| (route, amount)))
+        .max_by_key(|(_, amount)| *amount)
+        .ok_or(ContractError::NoSwapRoute {})
+}
+
+/// [`find_best_route`] simulating each route on `dex_adaptor`.
+pub fn find_best_route_on_adaptor(
+    querier: &QuerierWrapper,
+    storage: &dyn Storage,
+    dex_adaptor: &Addr,
+    offer: &Asset,
+    ask: &AssetInfo,
+) -> Result<(SwapRoute, Uint128), ContractError> {
+    find_best_route(storage, offer, ask, MAX_HOPS, |route| {
+        Ok(
+            query_simulate_route_swap(querier, dex_adaptor, route.hops.clone(), offer.clone())?
+                .return_amount,
+        )
+    })
+}
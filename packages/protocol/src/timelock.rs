@@ -0,0 +1,213 @@
+use cosmwasm_std::{Addr, Api, Binary, Env, Event, Order, Response, StdResult, Storage, WasmMsg};
+use cw_storage_plus::{Bound, Item, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    access_control::{assert_role, Role},
+    error::ContractError,
+};
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+pub const TIMELOCK_QUEUED_EVENT: &str = "apollo/timelock_queued";
+pub const TIMELOCK_EXECUTED_EVENT: &str = "apollo/timelock_executed";
+pub const TIMELOCK_CANCELLED_EVENT: &str = "apollo/timelock_cancelled";
+
+pub const TIMELOCK_CONFIG: Item<TimelockConfig> = Item::new("timelock_config");
+pub const TIMELOCK_ACTION_COUNT: Item<u64> = Item::new("timelock_action_count");
+pub const QUEUED_ACTIONS: Map<u64, QueuedAction> = Map::new("timelock_queued_actions");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct TimelockConfig {
+    pub delay: u64,        // minimum seconds between queueing and execution
+    pub grace_period: u64, // seconds after the eta during which the action can be executed
+}
+
+impl TimelockConfig {
+    pub fn validate(&self) -> Result<(), ContractError> {
+        if self.delay == 0 || self.grace_period == 0 {
+            return Err(ContractError::InvalidTimelockConfig {});
+        }
+        Ok(())
+    }
+}
+
+/// Serialized execute message for `target`, executable between `eta` and the end of the grace
+/// period.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct QueuedAction {
+    pub id: u64,
+    pub target: Addr,
+    pub msg: Binary,
+    pub eta: u64,
+    pub queued_by: Addr,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[schemars(deny_unknown_fields)]
+pub enum TimelockExecuteMsg {
+    QueueAction {
+        target: String,
+        msg: Binary,
+        eta: u64, // block time in seconds, at least `delay` from now
+    },
+    ExecuteAction {
+        id: u64,
+    },
+    CancelAction {
+        id: u64,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[schemars(deny_unknown_fields)]
+pub enum TimelockQueryMsg {
+    TimelockConfig {},
+    QueuedAction {
+        id: u64,
+    },
+    QueuedActions {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct QueuedActionsResponse {
+    pub actions: Vec<QueuedAction>,
+}
+
+/// Increments the action counter and returns the new id. Ids are never reused.
+pub fn next_action_id(storage: &mut dyn Storage) -> StdResult<u64> {
+    let id = TIMELOCK_ACTION_COUNT.may_load(storage)?.unwrap_or_default() + 1;
+    TIMELOCK_ACTION_COUNT.save(storage, &id)?;
+    Ok(id)
+}
+
+/// Queues `msg` for `target`. Only the owner, as stored in the contract's config, can queue.
+#[allow(clippy::too_many_arguments)]
+pub fn queue_action(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    env: &Env,
+    sender: &Addr,
+    owner: &Addr,
+    target: &str,
+    msg: Binary,
+    eta: u64,
+) -> Result<Response, ContractError> {
+    if sender != owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    let config = TIMELOCK_CONFIG.load(storage)?;
+    let min_eta = env
+        .block
+        .time
+        .seconds()
+        .checked_add(config.delay)
+        .ok_or(ContractError::InvalidTimelockConfig {})?;
+    if eta < min_eta {
+        return Err(ContractError::InvalidTimelockEta { min_eta });
+    }
+
+    let action = QueuedAction {
+        id: next_action_id(storage)?,
+        target: api.addr_validate(target)?,
+        msg,
+        eta,
+        queued_by: sender.clone(),
+    };
+    QUEUED_ACTIONS.save(storage, action.id, &action)?;
+
+    Ok(Response::new().add_event(
+        Event::new(TIMELOCK_QUEUED_EVENT)
+            .add_attribute("id", action.id.to_string())
+            .add_attribute("target", action.target)
+            .add_attribute("eta", eta.to_string())
+            .add_attribute("msg", action.msg.to_base64()),
+    ))
+}
+
+/// Dispatches a queued action once its eta is reached. Anyone can execute, so the change
+/// applies even if the owner does not follow up.
+pub fn execute_action(
+    storage: &mut dyn Storage,
+    env: &Env,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let action = QUEUED_ACTIONS.load(storage, id)?;
+    let config = TIMELOCK_CONFIG.load(storage)?;
+    let now = env.block.time.seconds();
+    if now < action.eta {
+        return Err(ContractError::TimelockNotReady { eta: action.eta });
+    }
+    // an overflowing grace period never expires
+    if matches!(action.eta.checked_add(config.grace_period), Some(expiry) if now >= expiry) {
+        return Err(ContractError::TimelockExpired {});
+    }
+    QUEUED_ACTIONS.remove(storage, id);
+
+    Ok(Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: action.target.to_string(),
+            msg: action.msg,
+            funds: vec![],
+        })
+        .add_event(
+            Event::new(TIMELOCK_EXECUTED_EVENT)
+                .add_attribute("id", id.to_string())
+                .add_attribute("target", action.target),
+        ))
+}
+
+/// Drops a queued action. Guardians can cancel as well as the owner.
+pub fn cancel_action(
+    storage: &mut dyn Storage,
+    sender: &Addr,
+    owner: &Addr,
+    id: u64,
+) -> Result<Response, ContractError> {
+    if sender != owner {
+        assert_role(storage, Role::Guardian, sender)?;
+    }
+    QUEUED_ACTIONS.load(storage, id)?;
+    QUEUED_ACTIONS.remove(storage, id);
+
+    Ok(Response::new().add_event(
+        Event::new(TIMELOCK_CANCELLED_EVENT)
+            .add_attribute("id", id.to_string())
+            .add_attribute("sender", sender),
+    ))
+}
+
+/// Guard for messages that must go through the timelock: when the contract targets itself the
+/// queued action is dispatched with the contract as sender.
+pub fn assert_from_timelock(env: &Env, sender: &Addr) -> Result<(), ContractError> {
+    if *sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(())
+}
+
+pub fn query_queued_actions(
+    storage: &dyn Storage,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<QueuedActionsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let actions = QUEUED_ACTIONS
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, action)| action))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(QueuedActionsResponse { actions })
+}
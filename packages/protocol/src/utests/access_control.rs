@@ -0,0 +1,132 @@
+use crate::access_control::{
+    assert_any_role, assert_role, execute_access_control, grant_role, has_role, role_members,
+    AccessControlExecuteMsg, Role,
+};
+use crate::error::ContractError;
+use cosmwasm_std::testing::{mock_dependencies, mock_info};
+use cosmwasm_std::Addr;
+
+#[test]
+fn test_grant_and_revoke_role() {
+    // Given
+    let mut deps = mock_dependencies();
+    let owner = Addr::unchecked("owner");
+    grant_role(deps.as_mut().storage, Role::Owner, &owner).unwrap();
+
+    // When
+    execute_access_control(
+        deps.as_mut(),
+        mock_info("owner", &[]),
+        AccessControlExecuteMsg::GrantRole {
+            role: Role::Keeper,
+            address: "keeper".to_string(),
+        },
+    )
+    .unwrap();
+
+    // Then
+    let keeper = Addr::unchecked("keeper");
+    assert!(has_role(deps.as_ref().storage, Role::Keeper, &keeper));
+    assert!(!has_role(deps.as_ref().storage, Role::Feeder, &keeper));
+    assert_eq!(
+        assert_role(deps.as_ref().storage, Role::Owner, &keeper),
+        Err(ContractError::Unauthorized {})
+    );
+    assert_eq!(
+        assert_any_role(deps.as_ref().storage, &[Role::Owner, Role::Keeper], &keeper),
+        Ok(())
+    );
+
+    // When
+    execute_access_control(
+        deps.as_mut(),
+        mock_info("owner", &[]),
+        AccessControlExecuteMsg::RevokeRole {
+            role: Role::Keeper,
+            address: "keeper".to_string(),
+        },
+    )
+    .unwrap();
+
+    // Then
+    assert!(!has_role(deps.as_ref().storage, Role::Keeper, &keeper));
+}
+
+#[test]
+fn test_only_owner_grants_roles() {
+    let mut deps = mock_dependencies();
+    grant_role(
+        deps.as_mut().storage,
+        Role::Keeper,
+        &Addr::unchecked("keeper"),
+    )
+    .unwrap();
+
+    let res = execute_access_control(
+        deps.as_mut(),
+        mock_info("keeper", &[]),
+        AccessControlExecuteMsg::GrantRole {
+            role: Role::Owner,
+            address: "keeper".to_string(),
+        },
+    );
+
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+}
+
+#[test]
+fn test_last_owner_cannot_renounce() {
+    // Given
+    let mut deps = mock_dependencies();
+    grant_role(deps.as_mut().storage, Role::Owner, &Addr::unchecked("a")).unwrap();
+    grant_role(deps.as_mut().storage, Role::Owner, &Addr::unchecked("b")).unwrap();
+
+    // When
+    let first = execute_access_control(
+        deps.as_mut(),
+        mock_info("a", &[]),
+        AccessControlExecuteMsg::RenounceRole { role: Role::Owner },
+    );
+    let last = execute_access_control(
+        deps.as_mut(),
+        mock_info("b", &[]),
+        AccessControlExecuteMsg::RenounceRole { role: Role::Owner },
+    );
+
+    // Then
+    assert!(first.is_ok());
+    assert_eq!(last, Err(ContractError::LastOwner {}));
+    assert_eq!(
+        role_members(deps.as_ref().storage, Role::Owner, None, None).unwrap(),
+        vec![Addr::unchecked("b")]
+    );
+}
+
+#[test]
+fn test_role_members_are_paginated_per_role() {
+    let mut deps = mock_dependencies();
+    for feeder in ["f1", "f2", "f3"] {
+        grant_role(
+            deps.as_mut().storage,
+            Role::Feeder,
+            &Addr::unchecked(feeder),
+        )
+        .unwrap();
+    }
+    grant_role(
+        deps.as_mut().storage,
+        Role::FeeManager,
+        &Addr::unchecked("f0"),
+    )
+    .unwrap();
+
+    let members = role_members(
+        deps.as_ref().storage,
+        Role::Feeder,
+        Some(&Addr::unchecked("f1")),
+        Some(1),
+    )
+    .unwrap();
+
+    assert_eq!(members, vec![Addr::unchecked("f2")]);
+}
@@ -0,0 +1,125 @@
+use crate::collector::{
+    migrate_legacy_config, plan_distribution, plan_swaps_to_base, plan_swaps_to_targets, Config,
+    LegacyConfig, MigrateMsg, TargetAsset, LEGACY_CONFIG,
+};
+use crate::error::ContractError;
+use crate::utests::fixtures::native_info;
+use apollo_asset::asset::Asset;
+use cosmwasm_std::testing::mock_dependencies;
+use cosmwasm_std::{Addr, Decimal, Uint128};
+
+fn config() -> Config {
+    Config {
+        owner: Addr::unchecked("owner"),
+        distribution_contract: Addr::unchecked("warchest"),
+        apollo_factory: Addr::unchecked("factory"),
+        dex_adaptor_id: 1,
+        base_denom: native_info("uosmo"),
+        target_assets: vec![
+            TargetAsset {
+                info: native_info("uosmo"),
+                weight: 1,
+            },
+            TargetAsset {
+                info: native_info("uatom"),
+                weight: 3,
+            },
+        ],
+        max_spread: Decimal::percent(1),
+    }
+}
+
+#[test]
+fn test_plan_swaps() {
+    let config = config();
+    let dex = Addr::unchecked("dex");
+    let balances = vec![
+        native_info("uosmo").to_asset(Uint128::new(10)),
+        native_info("ujuno").to_asset(Uint128::new(5)),
+        native_info("uusdc").to_asset(Uint128::zero()),
+        native_info("uatom").to_asset(Uint128::new(7)),
+    ];
+
+    let to_base = plan_swaps_to_base(&config, &balances, &dex);
+    assert_eq!(to_base.len(), 1);
+    assert_eq!(
+        to_base[0].offer,
+        native_info("ujuno").to_asset(Uint128::new(5))
+    );
+
+    let to_targets = plan_swaps_to_targets(&config, Uint128::new(100), &dex).unwrap();
+    assert_eq!(to_targets.len(), 1);
+    assert_eq!(to_targets[0].offer.amount, Uint128::new(75));
+    assert_eq!(to_targets[0].ask, native_info("uatom"));
+
+    assert_eq!(
+        plan_distribution(&config, &balances),
+        vec![
+            native_info("uosmo").to_asset(Uint128::new(10)),
+            native_info("uatom").to_asset(Uint128::new(7)),
+        ]
+    );
+}
+
+#[test]
+fn test_config_validation() {
+    let mut config = config();
+    config.target_assets[1].weight = 0;
+    assert_eq!(
+        config.validate(),
+        Err(ContractError::InvalidTargetAssets {})
+    );
+
+    let mut config = self::config();
+    config.max_spread = Decimal::percent(101);
+    assert_eq!(config.validate(), Err(ContractError::InvalidMaxSpread {}));
+    config.max_spread = Decimal::one();
+    assert_eq!(config.validate(), Err(ContractError::InvalidMaxSpread {}));
+}
+
+#[test]
+fn test_migrate_legacy_config() {
+    // Given
+    let mut deps = mock_dependencies();
+    LEGACY_CONFIG
+        .save(
+            &mut deps.storage,
+            &LegacyConfig {
+                owner: Addr::unchecked("owner"),
+                distribution_contract: Addr::unchecked("warchest"),
+                terraswap_factory: Addr::unchecked("terraswap"),
+                base_denom: "uusd".to_string(),
+                aust_token: Addr::unchecked("aust"),
+                target_assets: vec![Asset {
+                    info: native_info("uluna"),
+                    amount: Uint128::new(7),
+                }],
+                max_spread: Decimal::percent(1),
+                anchor_market: Addr::unchecked("anchor"),
+            },
+        )
+        .unwrap();
+
+    // When
+    let config = migrate_legacy_config(
+        &mut deps.storage,
+        &deps.api,
+        MigrateMsg {
+            apollo_factory: "factory".to_string(),
+            dex_adaptor_id: 2,
+            target_assets: None,
+        },
+    )
+    .unwrap();
+
+    // Then
+    assert_eq!(config.base_denom, native_info("uusd"));
+    assert_eq!(
+        config.target_assets,
+        vec![TargetAsset {
+            info: native_info("uluna"),
+            weight: 7
+        }]
+    );
+    assert_eq!(config.total_weight().unwrap(), 7);
+}
@@ -0,0 +1,100 @@
+use crate::dex_adaptor::{
+    dex_adaptor_msgs, provide_liquidity_msgs, swap_msgs, DexAdaptorExecuteMsg,
+};
+use crate::utests::fixtures::native;
+use apollo_asset::asset::AssetInfo;
+use cosmwasm_std::{to_binary, Addr, Coin, CosmosMsg, Uint128, WasmMsg};
+use cw20::Cw20ExecuteMsg;
+
+#[test]
+fn test_native_swap_attaches_funds() {
+    let dex = Addr::unchecked("dex");
+    let offer = native("uosmo", 100);
+    let ask = AssetInfo::NativeToken {
+        denom: "uatom".to_string(),
+    };
+
+    let msgs = swap_msgs(&dex, None, offer.clone(), ask.clone(), None, None).unwrap();
+
+    assert_eq!(
+        msgs,
+        vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "dex".to_string(),
+            msg: to_binary(&DexAdaptorExecuteMsg::Swap {
+                pool: None,
+                offer,
+                ask,
+                minimum_receive: None,
+                recipient: None,
+            })
+            .unwrap(),
+            funds: vec![Coin::new(100, "uosmo")],
+        })]
+    );
+}
+
+#[test]
+fn test_cw20_assets_are_approved() {
+    // Given
+    let dex = Addr::unchecked("dex");
+    let token = AssetInfo::Token {
+        contract_addr: Addr::unchecked("token"),
+    }
+    .to_asset(Uint128::new(50));
+    let assets = vec![native("uosmo", 100), token, native("uatom", 10)];
+
+    // When
+    let msgs = provide_liquidity_msgs(&dex, "pair".to_string(), assets, None, None).unwrap();
+
+    // Then
+    assert_eq!(msgs.len(), 2);
+    assert_eq!(
+        msgs[0],
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "token".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                spender: "dex".to_string(),
+                amount: Uint128::new(50),
+                expires: None,
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+    match &msgs[1] {
+        CosmosMsg::Wasm(WasmMsg::Execute { funds, .. }) => assert_eq!(
+            funds,
+            &vec![Coin::new(10, "uatom"), Coin::new(100, "uosmo")]
+        ),
+        msg => panic!("unexpected message {:?}", msg),
+    }
+}
+
+#[test]
+fn test_native_assets_with_same_denom_are_merged() {
+    // Given
+    let dex = Addr::unchecked("dex");
+    let assets = vec![
+        native("uosmo", 100),
+        native("uatom", 10),
+        native("uosmo", 50),
+    ];
+    let msg = DexAdaptorExecuteMsg::ProvideLiquidity {
+        pool: "pair".to_string(),
+        assets: assets.clone(),
+        min_lp_out: None,
+        recipient: None,
+    };
+
+    // When
+    let msgs = dex_adaptor_msgs(&dex, &msg, &assets).unwrap();
+
+    // Then
+    match &msgs[..] {
+        [CosmosMsg::Wasm(WasmMsg::Execute { funds, .. })] => assert_eq!(
+            funds,
+            &vec![Coin::new(10, "uatom"), Coin::new(150, "uosmo")]
+        ),
+        msgs => panic!("unexpected messages {:?}", msgs),
+    }
+}
@@ -0,0 +1,140 @@
+use crate::distribution::{
+    append_distribution_period, distribute, pending_distribution, released_between, released_until,
+    save_distribution_schedule, Schedule, SchedulePeriod, ScheduleUnit,
+};
+use crate::error::ContractError;
+use cosmwasm_std::testing::{mock_dependencies, mock_env};
+use cosmwasm_std::{Decimal, Timestamp, Uint128};
+use cw20::Expiration;
+use test_case::test_case;
+
+fn schedule() -> Vec<(u64, u64, Uint128)> {
+    vec![(1, 100, Uint128::new(1_000)), (101, 200, Uint128::new(500))]
+}
+
+#[test_case(0 => Uint128::zero(); "before start")]
+#[test_case(50 => Uint128::new(500); "half of first period")]
+#[test_case(100 => Uint128::new(1_000); "end of first period")]
+#[test_case(150 => Uint128::new(1_250); "half of second period")]
+#[test_case(1_000 => Uint128::new(1_500); "after end")]
+fn test_released_until(at: u64) -> Uint128 {
+    released_until(&schedule(), at).unwrap()
+}
+
+#[test]
+fn test_released_between() {
+    assert_eq!(
+        released_between(&schedule(), 50, 150).unwrap(),
+        Uint128::new(750)
+    );
+    assert_eq!(
+        released_between(&schedule(), 150, 50),
+        Err(ContractError::InvalidDistributionWindow {})
+    );
+}
+
+#[test]
+fn test_distribute_moves_checkpoint() {
+    // Given
+    let mut deps = mock_dependencies();
+    save_distribution_schedule(&mut deps.storage, schedule()).unwrap();
+
+    // When distributing in uneven steps
+    let first = distribute(&mut deps.storage, 33).unwrap();
+    let second = distribute(&mut deps.storage, 167).unwrap();
+    let third = distribute(&mut deps.storage, 500).unwrap();
+
+    // Then nothing is lost to rounding and nothing is paid twice
+    assert_eq!(first + second + third, Uint128::new(1_500));
+    assert_eq!(
+        pending_distribution(&deps.storage, 600).unwrap(),
+        Uint128::zero()
+    );
+    assert_eq!(
+        distribute(&mut deps.storage, 10),
+        Err(ContractError::InvalidDistributionWindow {})
+    );
+}
+
+#[test]
+fn test_replacing_schedule_does_not_release_twice() {
+    // Given half of the first period distributed
+    let mut deps = mock_dependencies();
+    save_distribution_schedule(&mut deps.storage, schedule()).unwrap();
+    let distributed = distribute(&mut deps.storage, 50).unwrap();
+
+    // When the schedule is replaced by one covering the same range
+    save_distribution_schedule(&mut deps.storage, vec![(1, 100, Uint128::new(2_000))]).unwrap();
+    let pending = pending_distribution(&deps.storage, 100).unwrap();
+
+    // Then only the part of the new schedule after the checkpoint is released
+    assert_eq!(distributed, Uint128::new(500));
+    assert_eq!(pending, Uint128::new(1_000));
+}
+
+#[test]
+fn test_append_distribution_period() {
+    let mut deps = mock_dependencies();
+    save_distribution_schedule(&mut deps.storage, schedule()).unwrap();
+    distribute(&mut deps.storage, 200).unwrap();
+
+    assert_eq!(
+        append_distribution_period(&mut deps.storage, (150, 300, Uint128::new(1))),
+        Err(ContractError::OverlappedDistributionRanges {})
+    );
+    append_distribution_period(&mut deps.storage, (201, 300, Uint128::new(100))).unwrap();
+
+    assert_eq!(
+        distribute(&mut deps.storage, 250).unwrap(),
+        Uint128::new(50)
+    );
+}
+
+#[test]
+fn test_schedule_rejects_mixed_units() {
+    let schedule = Schedule {
+        periods: vec![SchedulePeriod {
+            start: Expiration::AtHeight(1),
+            end: Expiration::AtTime(Timestamp::from_seconds(100)),
+            amount: Uint128::new(1),
+        }],
+    };
+
+    assert_eq!(
+        schedule.unit(),
+        Err(ContractError::MixedDistributionScheduleUnits {})
+    );
+}
+
+#[test]
+fn test_schedule_convert_height_to_time() {
+    // Given a reference block at height 100 and 1000s, with 5s blocks
+    let mut block = mock_env().block;
+    block.height = 100;
+    block.time = Timestamp::from_seconds(1_000);
+    let schedule = Schedule::new(
+        ScheduleUnit::Height,
+        &[(101, 200, Uint128::new(10)), (201, 300, Uint128::new(20))],
+    );
+
+    // When
+    let converted = schedule
+        .convert(
+            ScheduleUnit::Time,
+            &block,
+            Decimal::from_ratio(5u128, 1u128),
+        )
+        .unwrap();
+
+    // Then
+    assert_eq!(
+        converted.to_periods().unwrap(),
+        (
+            ScheduleUnit::Time,
+            vec![
+                (1_005, 1_500, Uint128::new(10)),
+                (1_501, 2_000, Uint128::new(20))
+            ]
+        )
+    );
+}
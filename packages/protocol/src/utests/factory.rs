@@ -1,10 +1,21 @@
-use crate::factory::{next_dex_id, APOLLO_DEX_COUNT};
+use crate::access_control::{grant_role, Role};
+use crate::error::ContractError;
+use crate::factory::{
+    add_dex, allow_pass_message, batch_execute_strategy_reply, execute_strategies,
+    get_apollo_dex_adaptor_by_addr, get_pass_message_allowlist, migrate_legacy_dex_adaptors,
+    next_dex_id, pass_message, pass_message_variant, remove_dex, PassMessageAllowance,
+    APOLLO_DEX_ADAPTORS, APOLLO_DEX_COUNT, BATCH_EXECUTION, DEFAULT_STRATEGY_GAS_LIMIT,
+    LEGACY_APOLLO_DEX_ADAPTORS, LEGACY_APOLLO_DEX_COUNT, REPLY_BATCH_EXECUTE_STRATEGY,
+};
+use apollo_asset::pair::PairType;
 use cosmwasm_std::testing::mock_dependencies;
+use cosmwasm_std::{Addr, Binary, Reply, ReplyOn, SubMsgResult};
 use test_case::test_case;
 
-#[test_case(0u8 => 1u8; "init with 0, should return 1")]
-#[test_case(255u8 => panics "attempt to add with overflow" ; "init with 255, should overflow")]
-fn test_next_dex_id_ok(init: u8) -> u8 {
+#[test_case(0u64 => 1u64; "init with 0, should return 1")]
+#[test_case(255u64 => 256u64; "init with 255, should not overflow")]
+#[test_case(u64::MAX => panics "attempt to add with overflow" ; "init with max, should overflow")]
+fn test_next_dex_id_ok(init: u64) -> u64 {
     // Given
     let mut deps = mock_dependencies();
     APOLLO_DEX_COUNT.save(&mut deps.storage, &init).unwrap();
@@ -12,3 +23,282 @@ fn test_next_dex_id_ok(init: u8) -> u8 {
     // When call the next_dex_id function with init records
     next_dex_id(&mut deps.storage).unwrap()
 }
+
+#[test]
+fn test_dex_registry_reverse_index() {
+    // Given
+    let mut deps = mock_dependencies();
+    let dex_addr = Addr::unchecked("dex_adaptor");
+
+    // When
+    let id = add_dex(
+        &mut deps.storage,
+        &dex_addr,
+        "osmosis".to_string(),
+        vec![PairType::Xyk {}],
+    )
+    .unwrap();
+
+    // Then
+    let dex = get_apollo_dex_adaptor_by_addr(&deps.storage, &dex_addr).unwrap();
+    assert_eq!(dex.id, id);
+    assert_eq!(dex.name, "osmosis");
+    assert!(add_dex(&mut deps.storage, &dex_addr, "dup".to_string(), vec![]).is_err());
+
+    remove_dex(&mut deps.storage, id).unwrap();
+    assert!(get_apollo_dex_adaptor_by_addr(&deps.storage, &dex_addr).is_err());
+    assert_eq!(
+        add_dex(&mut deps.storage, &dex_addr, "osmosis".to_string(), vec![]).unwrap(),
+        id + 1
+    );
+}
+
+#[test]
+fn test_migrate_legacy_dex_adaptors_twice_keeps_ids() {
+    // Given
+    let mut deps = mock_dependencies();
+    LEGACY_APOLLO_DEX_ADAPTORS
+        .save(
+            &mut deps.storage,
+            3u8,
+            &Addr::unchecked("legacy_dex_adaptor"),
+        )
+        .unwrap();
+    LEGACY_APOLLO_DEX_COUNT
+        .save(&mut deps.storage, &4u8)
+        .unwrap();
+
+    // When
+    migrate_legacy_dex_adaptors(&mut deps.storage).unwrap();
+    let first = add_dex(
+        &mut deps.storage,
+        &Addr::unchecked("dex_adaptor"),
+        "osmosis".to_string(),
+        vec![],
+    )
+    .unwrap();
+    let migrated = migrate_legacy_dex_adaptors(&mut deps.storage).unwrap();
+    let second = add_dex(
+        &mut deps.storage,
+        &Addr::unchecked("other_dex_adaptor"),
+        "astroport".to_string(),
+        vec![],
+    )
+    .unwrap();
+
+    // Then
+    assert_eq!(migrated, 0);
+    assert_eq!(first, 5);
+    assert_eq!(second, 6);
+    assert_eq!(
+        APOLLO_DEX_ADAPTORS.load(&deps.storage, 5).unwrap().name,
+        "osmosis"
+    );
+}
+
+#[test]
+fn test_migrate_without_legacy_state_keeps_count() {
+    // Given
+    let mut deps = mock_dependencies();
+    APOLLO_DEX_COUNT.save(&mut deps.storage, &7).unwrap();
+
+    // When
+    migrate_legacy_dex_adaptors(&mut deps.storage).unwrap();
+
+    // Then
+    assert_eq!(APOLLO_DEX_COUNT.load(&deps.storage).unwrap(), 7);
+}
+
+#[test]
+fn test_migrate_legacy_dex_adaptors() {
+    // Given
+    let mut deps = mock_dependencies();
+    let dex_addr = Addr::unchecked("legacy_dex_adaptor");
+    LEGACY_APOLLO_DEX_ADAPTORS
+        .save(&mut deps.storage, 3u8, &dex_addr)
+        .unwrap();
+    LEGACY_APOLLO_DEX_COUNT
+        .save(&mut deps.storage, &4u8)
+        .unwrap();
+
+    // When
+    let migrated = migrate_legacy_dex_adaptors(&mut deps.storage).unwrap();
+
+    // Then
+    assert_eq!(migrated, 1);
+    assert_eq!(
+        APOLLO_DEX_ADAPTORS.load(&deps.storage, 3).unwrap().address,
+        dex_addr
+    );
+    assert_eq!(
+        get_apollo_dex_adaptor_by_addr(&deps.storage, &dex_addr)
+            .unwrap()
+            .id,
+        3
+    );
+    assert_eq!(next_dex_id(&mut deps.storage).unwrap(), 5);
+    assert!(LEGACY_APOLLO_DEX_COUNT
+        .may_load(&deps.storage)
+        .unwrap()
+        .is_none());
+}
+
+#[test_case(br#"{"execute_strategy":{"strategy_id":1}}"# => Ok("execute_strategy".to_string()); "compact")]
+#[test_case(b" {\n  \"claim\" : {}}" => Ok("claim".to_string()); "whitespace")]
+#[test_case(br#"["claim"]"# => Err(ContractError::InvalidPassMessage {}); "not an object")]
+#[test_case(br#"{"cl\"aim":{}}"# => Err(ContractError::InvalidPassMessage {}); "escaped key")]
+#[test_case(b"{}" => Err(ContractError::InvalidPassMessage {}); "empty")]
+fn test_pass_message_variant(msg: &[u8]) -> Result<String, ContractError> {
+    pass_message_variant(&Binary::from(msg))
+}
+
+#[test]
+fn test_keeper_passes_allowlisted_messages_only() {
+    // Given
+    let mut deps = mock_dependencies();
+    let keeper = Addr::unchecked("keeper");
+    let strategy = Addr::unchecked("strategy");
+    grant_role(&mut deps.storage, Role::Keeper, &keeper).unwrap();
+    // a stale owner role must not grant anything, the config owner is the source of truth
+    grant_role(
+        &mut deps.storage,
+        Role::Owner,
+        &Addr::unchecked("old_owner"),
+    )
+    .unwrap();
+    let owner = Addr::unchecked("owner");
+    allow_pass_message(&mut deps.storage, &strategy, "execute_strategy").unwrap();
+    let allowed = Binary::from(br#"{"execute_strategy":{}}"#);
+    let denied = Binary::from(br#"{"update_guardian":{"guardian":"keeper"}}"#);
+
+    // When
+    let passed = pass_message(&deps.storage, &keeper, &owner, &strategy, allowed);
+    let rejected = pass_message(&deps.storage, &keeper, &owner, &strategy, denied.clone());
+    let by_old_owner = pass_message(
+        &deps.storage,
+        &Addr::unchecked("old_owner"),
+        &owner,
+        &strategy,
+        denied.clone(),
+    );
+    let by_owner = pass_message(&deps.storage, &owner, &owner, &strategy, denied);
+
+    // Then
+    assert_eq!(passed.unwrap().messages.len(), 1);
+    assert_eq!(
+        rejected,
+        Err(ContractError::PassMessageNotAllowed {
+            contract_addr: "strategy".to_string(),
+            variant: "update_guardian".to_string(),
+        })
+    );
+    assert!(matches!(
+        by_old_owner,
+        Err(ContractError::RoleNotGranted { .. })
+    ));
+    assert!(by_owner.is_ok());
+    assert_eq!(
+        get_pass_message_allowlist(&deps.storage, None, None).unwrap(),
+        vec![PassMessageAllowance {
+            contract_addr: strategy,
+            variant: "execute_strategy".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn test_execute_strategies_aggregates_outcomes() {
+    // Given
+    let mut deps = mock_dependencies();
+    let strategies: Vec<(u64, Addr)> = (1..=3)
+        .map(|id| (id, Addr::unchecked(format!("strategy_{}", id))))
+        .collect();
+    let res = execute_strategies(
+        &mut deps.storage,
+        &Addr::unchecked("keeper"),
+        &strategies,
+        false,
+        DEFAULT_STRATEGY_GAS_LIMIT,
+    )
+    .unwrap();
+
+    // When
+    let reply = |i: u64, error: &str| Reply {
+        id: REPLY_BATCH_EXECUTE_STRATEGY + i,
+        result: SubMsgResult::Err(error.to_string()),
+    };
+    batch_execute_strategy_reply(&mut deps.storage, reply(0, "not optimal to execute")).unwrap();
+    batch_execute_strategy_reply(&mut deps.storage, reply(2, "out of gas")).unwrap();
+
+    // Then
+    assert_eq!(res.messages.len(), 3);
+    assert!(res
+        .messages
+        .iter()
+        .all(|m| m.reply_on == ReplyOn::Error && m.gas_limit == Some(DEFAULT_STRATEGY_GAS_LIMIT)));
+    let batch = BATCH_EXECUTION.load(&deps.storage).unwrap();
+    assert_eq!(batch.ran, vec![2]);
+    assert_eq!(batch.skipped, vec![1]);
+    assert_eq!(batch.failed, vec![3]);
+}
+
+#[test]
+fn test_execute_strategies_stop_on_error() {
+    let mut deps = mock_dependencies();
+    let strategies = vec![(1, Addr::unchecked("strategy_1"))];
+    execute_strategies(
+        &mut deps.storage,
+        &Addr::unchecked("keeper"),
+        &strategies,
+        true,
+        DEFAULT_STRATEGY_GAS_LIMIT,
+    )
+    .unwrap();
+
+    let res = batch_execute_strategy_reply(
+        &mut deps.storage,
+        Reply {
+            id: REPLY_BATCH_EXECUTE_STRATEGY,
+            result: SubMsgResult::Err("out of gas".to_string()),
+        },
+    );
+
+    assert!(res.is_err());
+}
+
+#[test_case(vec![] ; "empty")]
+#[test_case(vec![1, 1] ; "duplicates")]
+#[test_case((0..51).collect() ; "too many")]
+fn test_execute_strategies_invalid_batch(strategy_ids: Vec<u64>) {
+    let mut deps = mock_dependencies();
+    let strategies: Vec<(u64, Addr)> = strategy_ids
+        .into_iter()
+        .map(|id| (id, Addr::unchecked("strategy")))
+        .collect();
+
+    let res = execute_strategies(
+        &mut deps.storage,
+        &Addr::unchecked("keeper"),
+        &strategies,
+        false,
+        DEFAULT_STRATEGY_GAS_LIMIT,
+    );
+
+    assert_eq!(res, Err(ContractError::InvalidBatchExecution {}));
+}
+
+#[test]
+fn test_execute_strategies_requires_gas() {
+    let mut deps = mock_dependencies();
+    let strategies = vec![(1, Addr::unchecked("strategy_1"))];
+
+    let res = execute_strategies(
+        &mut deps.storage,
+        &Addr::unchecked("keeper"),
+        &strategies,
+        false,
+        0,
+    );
+
+    assert_eq!(res, Err(ContractError::InvalidBatchExecution {}));
+}
@@ -0,0 +1,14 @@
+use apollo_asset::asset::{Asset, AssetInfo};
+use cosmwasm_std::Uint128;
+
+/// Native token `denom`.
+pub fn native_info(denom: &str) -> AssetInfo {
+    AssetInfo::NativeToken {
+        denom: denom.to_string(),
+    }
+}
+
+/// `amount` of the native token `denom`.
+pub fn native(denom: &str, amount: u128) -> Asset {
+    native_info(denom).to_asset(Uint128::new(amount))
+}
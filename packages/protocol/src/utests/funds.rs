@@ -0,0 +1,34 @@
+use crate::error::ContractError;
+use crate::funds::assert_funds_match;
+use crate::utests::fixtures::native;
+use apollo_asset::asset::{Asset, AssetInfo};
+use cosmwasm_std::{Addr, Coin, Uint128};
+use test_case::test_case;
+
+#[test_case(vec![], vec![] => Ok(()); "nothing expected nothing sent")]
+#[test_case(vec![Coin::new(100, "uosmo"), Coin::new(50, "uatom")], vec![native("uatom", 50), native("uosmo", 100)] => Ok(()); "exact funds in any order")]
+#[test_case(vec![Coin::new(100, "uosmo")], vec![native("uosmo", 100), native("uatom", 0)] => Ok(()); "zero amount need not be sent")]
+#[test_case(vec![Coin::new(100, "uosmo")], vec![native("uosmo", 100), native("uatom", 50)] => Err(ContractError::MissingFunds { denom: "uatom".to_string(), expected: Uint128::new(50) }); "missing denom")]
+#[test_case(vec![Coin::new(90, "uosmo")], vec![native("uosmo", 100)] => Err(ContractError::FundsMismatch { denom: "uosmo".to_string(), expected: Uint128::new(100), received: Uint128::new(90) }); "wrong amount")]
+#[test_case(vec![Coin::new(100, "uosmo"), Coin::new(1, "uion")], vec![native("uosmo", 100)] => Err(ContractError::UnexpectedFunds { denom: "uion".to_string(), amount: Uint128::new(1) }); "extra denom")]
+#[test_case(vec![Coin::new(1, "uatom")], vec![native("uosmo", 100), native("uatom", 0)] => Err(ContractError::UnexpectedFunds { denom: "uatom".to_string(), amount: Uint128::new(1) }); "coin sent for zero amount")]
+#[test_case(vec![Coin::new(50, "uosmo"), Coin::new(50, "uosmo")], vec![native("uosmo", 100)] => Err(ContractError::DuplicateDenom { denom: "uosmo".to_string() }); "duplicate coin")]
+#[test_case(vec![Coin::new(100, "uosmo")], vec![native("uosmo", 100), native("uosmo", 100)] => Err(ContractError::DuplicateDenom { denom: "uosmo".to_string() }); "duplicate expected denom")]
+fn test_assert_funds_match(funds: Vec<Coin>, expected: Vec<Asset>) -> Result<(), ContractError> {
+    assert_funds_match(&funds, &expected)
+}
+
+#[test]
+fn test_assert_funds_match_skips_cw20_assets() {
+    // Given
+    let lp = AssetInfo::Token {
+        contract_addr: Addr::unchecked("lp"),
+    }
+    .to_asset(Uint128::new(100));
+
+    // When
+    let res = assert_funds_match(&[Coin::new(10, "uosmo")], &[lp, native("uosmo", 10)]);
+
+    // Then
+    assert_eq!(res, Ok(()));
+}
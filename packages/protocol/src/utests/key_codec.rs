@@ -0,0 +1,82 @@
+use crate::error::ContractError;
+use crate::key_codec::{
+    parse_addr_u64_key, parse_u64_addr_key, parse_u64_key, parse_u64_u64_key, parse_u8_key,
+    split_first_key, strip_namespace,
+};
+use cosmwasm_std::testing::MockStorage;
+use cosmwasm_std::{Addr, Order, Storage};
+use cw_storage_plus::{Map, PrimaryKey};
+use test_case::test_case;
+
+/// Deterministic byte generator so the fuzz sweep is reproducible.
+fn pseudo_random_bytes(seed: u64, len: usize) -> Vec<u8> {
+    let mut state = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    (0..len)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 56) as u8
+        })
+        .collect()
+}
+
+#[test_case(&[7u8] => Ok(7u8); "single byte")]
+#[test_case(&[] => Err(ContractError::CorruptedData {}); "empty")]
+#[test_case(&[0u8, 7u8] => Err(ContractError::CorruptedData {}); "too long")]
+fn test_parse_u8_key(data: &[u8]) -> Result<u8, ContractError> {
+    parse_u8_key(data)
+}
+
+#[test_case(&[0, 0, 0, 0, 0, 0, 1, 2] => Ok(258u64); "eight bytes")]
+#[test_case(&[1, 2] => Err(ContractError::CorruptedData {}); "too short")]
+fn test_parse_u64_key(data: &[u8]) -> Result<u64, ContractError> {
+    parse_u64_key(data)
+}
+
+#[test]
+fn test_key_codecs_never_panic() {
+    for seed in 0..512u64 {
+        let data = pseudo_random_bytes(seed, (seed % 24) as usize);
+
+        assert_eq!(parse_u8_key(&data).is_ok(), data.len() == 1);
+        assert_eq!(parse_u64_key(&data).is_ok(), data.len() == 8);
+        let _ = split_first_key(&data);
+        let _ = strip_namespace(b"namespace", &data);
+        let _ = parse_u64_u64_key(&data);
+        let _ = parse_u64_addr_key(&data);
+        let _ = parse_addr_u64_key(&data);
+    }
+}
+
+#[test]
+fn test_composite_keys_roundtrip_storage_plus() {
+    let key = (42u64, &Addr::unchecked("user")).joined_key();
+    assert_eq!(
+        parse_u64_addr_key(&key).unwrap(),
+        (42u64, Addr::unchecked("user"))
+    );
+
+    let key = (&Addr::unchecked("user"), 42u64).joined_key();
+    assert_eq!(
+        parse_addr_u64_key(&key).unwrap(),
+        (Addr::unchecked("user"), 42u64)
+    );
+}
+
+#[test]
+fn test_strip_namespace_raw_storage() {
+    const ITEMS: Map<u64, bool> = Map::new("items");
+    let mut storage = MockStorage::new();
+    ITEMS.save(&mut storage, 9, &true).unwrap();
+
+    let (raw_key, _) = storage.range(None, None, Order::Ascending).next().unwrap();
+    let key = strip_namespace(b"items", &raw_key).unwrap();
+    assert_eq!(parse_u64_key(key).unwrap(), 9);
+    assert_eq!(
+        strip_namespace(b"other", &raw_key),
+        Err(ContractError::CorruptedData {})
+    );
+}
@@ -1,5 +1,18 @@
 #[cfg(test)]
+mod access_control;
+mod collector;
+mod dex_adaptor;
+mod distribution;
 mod factory;
+pub mod fixtures;
+mod funds;
+mod key_codec;
 mod oracle;
+mod ownership;
 mod querier;
+mod slippage;
+mod strategy_token;
+mod swap_route;
+mod timelock;
 mod utils;
+mod zap;
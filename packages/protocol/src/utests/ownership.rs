@@ -0,0 +1,161 @@
+use crate::error::ContractError;
+use crate::ownership::{
+    claim_ownership, drop_ownership_proposal, propose_new_owner, query_ownership_proposal,
+    OwnershipExecuteMsg, MAX_PROPOSAL_EXPIRY,
+};
+use crate::{collector, factory, oracle};
+use cosmwasm_std::testing::{mock_dependencies, mock_env};
+use cosmwasm_std::{from_slice, Addr, StdResult, Storage};
+use cw_storage_plus::Item;
+use test_case::test_case;
+
+const OWNER: Item<Addr> = Item::new("owner");
+
+fn save_owner(storage: &mut dyn Storage, owner: Addr) -> StdResult<()> {
+    OWNER.save(storage, &owner)
+}
+
+#[test]
+fn test_two_step_ownership_transfer() {
+    // Given
+    let mut deps = mock_dependencies();
+    let mut env = mock_env();
+    let owner = Addr::unchecked("owner");
+    let new_owner = Addr::unchecked("new_owner");
+    OWNER.save(deps.as_mut().storage, &owner).unwrap();
+
+    // When
+    propose_new_owner(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        &owner,
+        &owner,
+        "new_owner",
+        100,
+    )
+    .unwrap();
+    let stranger = claim_ownership(
+        deps.as_mut().storage,
+        &env,
+        &Addr::unchecked("stranger"),
+        save_owner,
+    );
+    env.block.time = env.block.time.plus_seconds(99);
+    claim_ownership(deps.as_mut().storage, &env, &new_owner, save_owner).unwrap();
+
+    // Then
+    assert_eq!(stranger, Err(ContractError::Unauthorized {}));
+    assert_eq!(OWNER.load(deps.as_ref().storage).unwrap(), new_owner);
+    assert_eq!(
+        query_ownership_proposal(deps.as_ref().storage)
+            .unwrap()
+            .proposal,
+        None
+    );
+}
+
+#[test]
+fn test_expired_proposal_cannot_be_claimed() {
+    // Given
+    let mut deps = mock_dependencies();
+    let mut env = mock_env();
+    let owner = Addr::unchecked("owner");
+    OWNER.save(deps.as_mut().storage, &owner).unwrap();
+    propose_new_owner(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        &owner,
+        &owner,
+        "new_owner",
+        100,
+    )
+    .unwrap();
+
+    // When
+    env.block.time = env.block.time.plus_seconds(100);
+    let res = claim_ownership(
+        deps.as_mut().storage,
+        &env,
+        &Addr::unchecked("new_owner"),
+        save_owner,
+    );
+
+    // Then
+    assert_eq!(res, Err(ContractError::OwnershipProposalExpired {}));
+    assert_eq!(OWNER.load(deps.as_ref().storage).unwrap(), owner);
+}
+
+#[test]
+fn test_dropped_proposal_cannot_be_claimed() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let owner = Addr::unchecked("owner");
+    propose_new_owner(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        &owner,
+        &owner,
+        "new_owner",
+        100,
+    )
+    .unwrap();
+
+    drop_ownership_proposal(deps.as_mut().storage, &owner, &owner).unwrap();
+    let res = claim_ownership(
+        deps.as_mut().storage,
+        &env,
+        &Addr::unchecked("new_owner"),
+        save_owner,
+    );
+
+    assert_eq!(res, Err(ContractError::NoOwnershipProposal {}));
+}
+
+#[test_case("stranger", "new_owner", 100 => Err(ContractError::Unauthorized {}); "not the owner")]
+#[test_case("owner", "new_owner", 0 => Err(ContractError::InvalidOwnershipProposalExpiry {}); "no expiry")]
+#[test_case("owner", "new_owner", MAX_PROPOSAL_EXPIRY + 1 => Err(ContractError::InvalidOwnershipProposalExpiry {}); "expiry too long")]
+#[test_case("owner", "owner", 100 => Err(ContractError::InvalidOwnershipProposal {}); "already owner")]
+fn test_propose_new_owner_fails(
+    sender: &str,
+    new_owner: &str,
+    expiry: u64,
+) -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+
+    propose_new_owner(
+        &mut deps.storage,
+        &deps.api,
+        &mock_env(),
+        &Addr::unchecked(sender),
+        &Addr::unchecked("owner"),
+        new_owner,
+        expiry,
+    )
+    .map(|_| ())
+}
+
+#[test]
+fn test_ownership_messages_are_embedded() {
+    // Given
+    let propose = br#"{"ownership":{"propose_new_owner":{"owner":"new_owner","expiry":60}}}"#;
+    let expected = OwnershipExecuteMsg::ProposeNewOwner {
+        owner: "new_owner".to_string(),
+        expiry: 60,
+    };
+
+    // When
+    let factory_msg: factory::ExecuteMsg = from_slice(propose).unwrap();
+    let oracle_msg: oracle::ExecuteMsg = from_slice(propose).unwrap();
+    let collector_msg: collector::ExecuteMsg = from_slice(propose).unwrap();
+
+    // Then
+    assert_eq!(
+        factory_msg,
+        factory::ExecuteMsg::Ownership(expected.clone())
+    );
+    assert_eq!(oracle_msg, oracle::ExecuteMsg::Ownership(expected.clone()));
+    assert_eq!(collector_msg, collector::ExecuteMsg::Ownership(expected));
+}
@@ -0,0 +1,74 @@
+use crate::error::ContractError;
+use crate::slippage::{
+    check_oracle_deviation, minimum_receive, oracle_minimum_receive, SlippageConfig,
+};
+use apollo_asset::asset::AssetInfo;
+use cosmwasm_std::{Decimal, Decimal256, Uint128, Uint256};
+use test_case::test_case;
+
+#[test]
+fn test_minimum_receive() {
+    assert_eq!(
+        minimum_receive(Uint128::new(1_000), Decimal::percent(1)),
+        Uint128::new(990)
+    );
+}
+
+#[test_case(Decimal::percent(1), Decimal::percent(2) => Ok(()); "valid")]
+#[test_case(Decimal::one(), Decimal::percent(2) => Err(ContractError::InvalidMaxSpread); "spread")]
+#[test_case(Decimal::percent(1), Decimal::one() => Err(ContractError::InvalidMaxOracleDeviation {}); "deviation")]
+fn test_validate_slippage_config(
+    max_spread: Decimal,
+    max_oracle_deviation: Decimal,
+) -> Result<(), ContractError> {
+    SlippageConfig {
+        max_spread,
+        max_oracle_deviation,
+    }
+    .validate()
+}
+
+// 100 offer tokens priced 2 for ask tokens priced 4 should return 50 at the oracle rate
+#[test_case(50 => Ok(()); "oracle rate")]
+#[test_case(49 => Ok(()); "within deviation")]
+#[test_case(48 => Err(ContractError::OraclePriceDeviation { minimum: Uint256::from(49u128), actual: Uint128::new(48) }); "sandwiched")]
+fn test_check_oracle_deviation(return_amount: u128) -> Result<(), ContractError> {
+    let offer = AssetInfo::NativeToken {
+        denom: "uosmo".to_string(),
+    }
+    .to_asset(Uint128::new(100));
+
+    check_oracle_deviation(
+        &offer,
+        Uint128::new(return_amount),
+        Decimal256::percent(200),
+        Decimal256::percent(400),
+        Decimal::percent(2),
+    )
+}
+
+#[test]
+fn test_oracle_minimum_receive() {
+    // 100 offer tokens priced 2 for ask tokens priced 4 are worth 50 at the oracle rate
+    let offer = AssetInfo::NativeToken {
+        denom: "uosmo".to_string(),
+    }
+    .to_asset(Uint128::new(100));
+
+    assert_eq!(
+        oracle_minimum_receive(
+            &offer,
+            Decimal256::percent(200),
+            Decimal256::percent(400),
+            Decimal::percent(2),
+        ),
+        Ok(Uint128::new(49))
+    );
+    assert!(oracle_minimum_receive(
+        &offer,
+        Decimal256::percent(200),
+        Decimal256::zero(),
+        Decimal::percent(2),
+    )
+    .is_err());
+}
@@ -0,0 +1,118 @@
+use crate::error::ContractError;
+use crate::strategy::msg::ExecuteMsg as StrategyExecuteMsg;
+use crate::strategy_token::{execute, instantiate, InstantiateMsg, StrategyTokenHookMsg};
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+};
+use cosmwasm_std::{from_binary, to_binary, CosmosMsg, OwnedDeps, Uint128, WasmMsg};
+use cw20::{Cw20Coin, Cw20ExecuteMsg};
+use cw20_base::msg::InstantiateMsg as Cw20InstantiateMsg;
+
+fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+    let mut deps = mock_dependencies();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("strategy", &[]),
+        InstantiateMsg {
+            base_init_msg: Cw20InstantiateMsg {
+                name: "Strategy Token".to_string(),
+                symbol: "STRAT".to_string(),
+                decimals: 6,
+                initial_balances: vec![Cw20Coin {
+                    address: "alice".to_string(),
+                    amount: Uint128::new(100),
+                }],
+                mint: None,
+                marketing: None,
+            },
+            apollo_factory: "factory".to_string(),
+            strategy_id: 1,
+        },
+    )
+    .unwrap();
+    deps
+}
+
+#[test]
+fn test_strategy_token_mint_and_transfer_hook() {
+    // Given a strategy token owned by "strategy"
+    let mut deps = setup();
+
+    // When anyone else mints
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        Cw20ExecuteMsg::Mint {
+            recipient: "alice".to_string(),
+            amount: Uint128::new(1),
+        },
+    )
+    .unwrap_err();
+
+    // Then
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // When alice transfers
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        Cw20ExecuteMsg::Transfer {
+            recipient: "bob".to_string(),
+            amount: Uint128::new(10),
+        },
+    )
+    .unwrap();
+
+    // Then the strategy is notified with the balances before the transfer
+    match &res.messages[0].msg {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr, msg, ..
+        }) => {
+            assert_eq!(contract_addr, "strategy");
+            assert_eq!(
+                from_binary::<StrategyExecuteMsg>(msg).unwrap(),
+                StrategyExecuteMsg::StrategyTokenHook(StrategyTokenHookMsg::SharesTransferred {
+                    from: "alice".to_string(),
+                    to: "bob".to_string(),
+                    amount: Uint128::new(10),
+                    from_balance: Uint128::new(100),
+                    to_balance: Uint128::zero(),
+                })
+            );
+        }
+        msg => panic!("unexpected message {:?}", msg),
+    }
+}
+
+#[test]
+fn test_strategy_token_send_checkpoints_before_callback() {
+    // Given
+    let mut deps = setup();
+
+    // When alice sends shares to a contract
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        Cw20ExecuteMsg::Send {
+            contract: "vault".to_string(),
+            amount: Uint128::new(10),
+            msg: to_binary("callback").unwrap(),
+        },
+    )
+    .unwrap();
+
+    // Then the strategy hook runs before the receiver callback
+    let targets: Vec<_> = res
+        .messages
+        .iter()
+        .map(|sub| match &sub.msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }) => contract_addr.as_str(),
+            msg => panic!("unexpected message {:?}", msg),
+        })
+        .collect();
+    assert_eq!(targets, vec!["strategy", "vault"]);
+}
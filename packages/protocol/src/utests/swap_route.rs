@@ -0,0 +1,117 @@
+use crate::dex_adaptor::SwapOperation;
+use crate::error::ContractError;
+use crate::swap_route::{find_best_route, find_routes, register_pair, SwapRoute, MAX_HOPS};
+use crate::utests::fixtures::native_info;
+use apollo_asset::pair::{PairInfo, PairType};
+use cosmwasm_std::testing::mock_dependencies;
+use cosmwasm_std::{Addr, StdError, Storage, Uint128};
+use test_case::test_case;
+
+fn hop(pool: &str, offer: &str, ask: &str) -> SwapOperation {
+    SwapOperation {
+        pool: pool.to_string(),
+        offer: native_info(offer),
+        ask: native_info(ask),
+    }
+}
+
+fn register(storage: &mut dyn Storage, pair: &str, a: &str, b: &str) {
+    register_pair(
+        storage,
+        &PairInfo {
+            asset_infos: [native_info(a), native_info(b)],
+            contract_addr: Addr::unchecked(pair),
+            liquidity_token: None,
+            pair_type: PairType::Xyk {},
+        },
+    )
+    .unwrap();
+}
+
+#[test_case(vec![hop("p1", "a", "b"), hop("p2", "b", "c")] => Ok(()); "connected")]
+#[test_case(vec![] => Err(ContractError::InvalidSwapRoute {}); "empty")]
+#[test_case(vec![hop("p1", "a", "b"), hop("p2", "c", "d")] => Err(ContractError::InvalidSwapRoute {}); "disconnected")]
+#[test_case(vec![hop("p1", "a", "b"), hop("p1", "b", "a")] => Err(ContractError::InvalidSwapRoute {}); "cycle")]
+#[test_case(vec![hop("p1", "a", "a")] => Err(ContractError::InvalidSwapRoute {}); "same asset")]
+fn test_validate_route(hops: Vec<SwapOperation>) -> Result<(), ContractError> {
+    SwapRoute { hops }.validate()
+}
+
+#[test]
+fn test_find_routes() {
+    // Given
+    let mut deps = mock_dependencies();
+    register(deps.as_mut().storage, "p1", "a", "b");
+    register(deps.as_mut().storage, "p2", "b", "c");
+    register(deps.as_mut().storage, "p3", "a", "c");
+    register(deps.as_mut().storage, "p4", "c", "d");
+
+    // When
+    let routes = find_routes(
+        deps.as_ref().storage,
+        &native_info("a"),
+        &native_info("c"),
+        MAX_HOPS,
+    )
+    .unwrap();
+
+    // Then
+    assert_eq!(routes.len(), 2);
+    assert!(routes.contains(&SwapRoute {
+        hops: vec![hop("p3", "a", "c")]
+    }));
+    assert!(routes.contains(&SwapRoute {
+        hops: vec![hop("p1", "a", "b"), hop("p2", "b", "c")]
+    }));
+    for route in routes {
+        route.validate().unwrap();
+    }
+}
+
+#[test]
+fn test_find_best_route_by_simulated_output() {
+    // Given
+    let mut deps = mock_dependencies();
+    register(deps.as_mut().storage, "p1", "a", "b");
+    register(deps.as_mut().storage, "p2", "b", "c");
+    register(deps.as_mut().storage, "p3", "a", "c");
+    register(deps.as_mut().storage, "p4", "a", "d");
+    register(deps.as_mut().storage, "p5", "d", "c");
+    let offer = native_info("a").to_asset(Uint128::new(100));
+
+    // When the direct pool is shallow and the route through d fails to simulate
+    let (route, amount) = find_best_route(
+        deps.as_ref().storage,
+        &offer,
+        &native_info("c"),
+        MAX_HOPS,
+        |route| match route.hops[0].pool.as_str() {
+            "p1" => Ok(Uint128::new(95)),
+            "p3" => Ok(Uint128::new(80)),
+            _ => Err(StdError::generic_err("empty pool")),
+        },
+    )
+    .unwrap();
+
+    // Then
+    assert_eq!(route.hops, vec![hop("p1", "a", "b"), hop("p2", "b", "c")]);
+    assert_eq!(amount, Uint128::new(95));
+}
+
+#[test]
+fn test_no_route() {
+    let mut deps = mock_dependencies();
+    register(deps.as_mut().storage, "p1", "a", "b");
+    let offer = native_info("a").to_asset(Uint128::new(100));
+
+    let err = find_best_route(
+        deps.as_ref().storage,
+        &offer,
+        &native_info("c"),
+        MAX_HOPS,
+        |_| Ok(Uint128::zero()),
+    )
+    .unwrap_err();
+
+    assert_eq!(err, ContractError::NoSwapRoute {});
+}
@@ -0,0 +1,182 @@
+use crate::access_control::{grant_role, Role};
+use crate::error::ContractError;
+use crate::timelock::{
+    cancel_action, execute_action, query_queued_actions, queue_action, TimelockConfig,
+    TIMELOCK_CONFIG,
+};
+use cosmwasm_std::testing::{mock_dependencies, mock_env, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{to_binary, Addr, CosmosMsg, Empty, Env, OwnedDeps, WasmMsg};
+
+const DELAY: u64 = 100;
+
+fn setup() -> (OwnedDeps<MockStorage, MockApi, MockQuerier>, Env) {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    TIMELOCK_CONFIG
+        .save(
+            &mut deps.storage,
+            &TimelockConfig {
+                delay: DELAY,
+                grace_period: 50,
+            },
+        )
+        .unwrap();
+    grant_role(
+        &mut deps.storage,
+        Role::Guardian,
+        &Addr::unchecked("guardian"),
+    )
+    .unwrap();
+    queue_action(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        &Addr::unchecked("owner"),
+        &Addr::unchecked("owner"),
+        "factory",
+        to_binary(&Empty {}).unwrap(),
+        env.block.time.seconds() + DELAY,
+    )
+    .unwrap();
+    (deps, env)
+}
+
+#[test]
+fn test_execute_after_delay() {
+    // Given
+    let (mut deps, mut env) = setup();
+    let eta = env.block.time.seconds() + DELAY;
+
+    // When
+    let early = execute_action(&mut deps.storage, &env, 1);
+    env.block.time = env.block.time.plus_seconds(DELAY);
+    let res = execute_action(&mut deps.storage, &env, 1).unwrap();
+
+    // Then
+    assert_eq!(early, Err(ContractError::TimelockNotReady { eta }));
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "factory".to_string(),
+            msg: to_binary(&Empty {}).unwrap(),
+            funds: vec![],
+        })
+    );
+    assert!(query_queued_actions(&deps.storage, None, None)
+        .unwrap()
+        .actions
+        .is_empty());
+}
+
+#[test]
+fn test_execute_after_grace_period_fails() {
+    let (mut deps, mut env) = setup();
+
+    env.block.time = env.block.time.plus_seconds(DELAY + 50);
+    let res = execute_action(&mut deps.storage, &env, 1);
+
+    assert_eq!(res, Err(ContractError::TimelockExpired {}));
+}
+
+#[test]
+fn test_queue_before_delay_fails() {
+    let (mut deps, env) = setup();
+    let now = env.block.time.seconds();
+
+    let res = queue_action(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        &Addr::unchecked("owner"),
+        &Addr::unchecked("owner"),
+        "factory",
+        to_binary(&Empty {}).unwrap(),
+        now + DELAY - 1,
+    );
+
+    assert_eq!(
+        res,
+        Err(ContractError::InvalidTimelockEta {
+            min_eta: now + DELAY
+        })
+    );
+}
+
+#[test]
+fn test_guardian_cancels_but_cannot_queue() {
+    // Given
+    let (mut deps, env) = setup();
+    let guardian = Addr::unchecked("guardian");
+
+    // When
+    let queued = queue_action(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        &guardian,
+        &Addr::unchecked("owner"),
+        "factory",
+        to_binary(&Empty {}).unwrap(),
+        env.block.time.seconds() + DELAY,
+    );
+    let owner = Addr::unchecked("owner");
+    let stranger = cancel_action(&mut deps.storage, &Addr::unchecked("stranger"), &owner, 1);
+    cancel_action(&mut deps.storage, &guardian, &owner, 1).unwrap();
+
+    // Then
+    assert_eq!(queued, Err(ContractError::Unauthorized {}));
+    assert_eq!(stranger, Err(ContractError::Unauthorized {}));
+    assert!(query_queued_actions(&deps.storage, None, None)
+        .unwrap()
+        .actions
+        .is_empty());
+}
+
+#[test]
+fn test_only_current_owner_queues() {
+    // Given ownership moved from "owner" to "new_owner" in the config
+    let (mut deps, env) = setup();
+    let new_owner = Addr::unchecked("new_owner");
+    let queue = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, sender: &str| {
+        queue_action(
+            &mut deps.storage,
+            &deps.api,
+            &env,
+            &Addr::unchecked(sender),
+            &new_owner,
+            "factory",
+            to_binary(&Empty {}).unwrap(),
+            env.block.time.seconds() + DELAY,
+        )
+    };
+
+    // When
+    let old = queue(&mut deps, "owner");
+    let new = queue(&mut deps, "new_owner");
+
+    // Then
+    assert_eq!(old, Err(ContractError::Unauthorized {}));
+    assert!(new.is_ok());
+}
+
+#[test]
+fn test_overflowing_grace_period_does_not_expire() {
+    // Given
+    let (mut deps, mut env) = setup();
+    TIMELOCK_CONFIG
+        .save(
+            &mut deps.storage,
+            &TimelockConfig {
+                delay: DELAY,
+                grace_period: u64::MAX,
+            },
+        )
+        .unwrap();
+
+    // When
+    env.block.time = env.block.time.plus_seconds(DELAY * 10);
+    let res = execute_action(&mut deps.storage, &env, 1);
+
+    // Then
+    assert!(res.is_ok());
+}
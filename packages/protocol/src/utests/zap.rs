@@ -0,0 +1,136 @@
+use crate::error::ContractError;
+use crate::utests::fixtures::native_info;
+use crate::zap::{optimal_swap_amount, plan_zap_in, plan_zap_out, ZapAction, ZapPool};
+use apollo_asset::asset::{Asset, AssetInfo};
+use apollo_asset::pair::{PairInfo, PairType};
+use cosmwasm_std::{Addr, Decimal, Uint128};
+use test_case::test_case;
+
+fn pool(pair_type: PairType, reserve: u128, commission: Decimal) -> ZapPool {
+    ZapPool {
+        pair: PairInfo {
+            asset_infos: [native_info("uosmo"), native_info("uatom")],
+            contract_addr: Addr::unchecked("pair"),
+            liquidity_token: None,
+            pair_type,
+        },
+        reserves: [
+            native_info("uosmo").to_asset(Uint128::new(reserve)),
+            native_info("uatom").to_asset(Uint128::new(reserve)),
+        ],
+        lp_token: native_info("gamm/pool/1"),
+        total_share: Uint128::new(reserve),
+        commission,
+    }
+}
+
+#[test_case(1_000, 100, Decimal::zero() => Uint128::new(48); "no fee")]
+#[test_case(1_000_000, 100_000, Decimal::permille(3) => Uint128::new(48_882); "xyk fee")]
+#[test_case(1_000_000, 0, Decimal::permille(3) => Uint128::zero(); "nothing to swap")]
+fn test_optimal_swap_amount(offer_pool: u128, amount: u128, commission: Decimal) -> Uint128 {
+    optimal_swap_amount(Uint128::new(offer_pool), Uint128::new(amount), commission).unwrap()
+}
+
+#[test_case(Decimal::one(); "whole offer as fee")]
+#[test_case(Decimal::percent(150); "fee above the offer")]
+fn test_optimal_swap_amount_rejects_invalid_fee(commission: Decimal) {
+    assert_eq!(
+        optimal_swap_amount(Uint128::new(1_000), Uint128::new(100), commission),
+        Err(ContractError::InvalidFee)
+    );
+}
+
+#[test]
+fn test_plan_zap_in() {
+    // Given
+    let pool = pool(PairType::Xyk {}, 1_000_000, Decimal::permille(3));
+    let offer = native_info("uosmo").to_asset(Uint128::new(100_000));
+
+    // When
+    let plan = plan_zap_in(&pool, &Addr::unchecked("dex"), &offer, Decimal::percent(1)).unwrap();
+
+    // Then
+    assert_eq!(
+        plan.actions,
+        vec![
+            ZapAction::Swap {
+                pair: Addr::unchecked("pair"),
+                offer: native_info("uosmo").to_asset(Uint128::new(48_882)),
+                ask: native_info("uatom"),
+                minimum_receive: Uint128::new(45_999),
+            },
+            ZapAction::ProvideLiquidity {
+                pair: Addr::unchecked("pair"),
+                assets: [
+                    native_info("uosmo").to_asset(Uint128::new(51_118)),
+                    native_info("uatom").to_asset(Uint128::new(46_464)),
+                ],
+                min_lp_out: Uint128::new(48_240),
+            },
+        ]
+    );
+    assert_eq!(plan.expected, Uint128::new(48_728));
+    assert_eq!(plan.minimum, Uint128::new(48_240));
+}
+
+#[test]
+fn test_plan_zap_out() {
+    // Given
+    let pool = pool(PairType::Xyk {}, 1_000_000, Decimal::permille(3));
+
+    // When
+    let plan = plan_zap_out(
+        &pool,
+        &Addr::unchecked("dex"),
+        Uint128::new(100_000),
+        &native_info("uosmo"),
+        Decimal::percent(1),
+    )
+    .unwrap();
+
+    // Then
+    assert_eq!(
+        plan.actions[1],
+        ZapAction::Swap {
+            pair: Addr::unchecked("pair"),
+            offer: native_info("uatom").to_asset(Uint128::new(100_000)),
+            ask: native_info("uosmo"),
+            minimum_receive: Uint128::new(88_832),
+        }
+    );
+    assert_eq!(plan.expected, Uint128::new(189_730));
+}
+
+#[test_case(PairType::None {}, native_info("uosmo"), Decimal::zero() => ContractError::UnsupportedPairType {}; "unsupported pair")]
+#[test_case(PairType::Stable {}, native_info("uosmo"), Decimal::zero() => ContractError::UnsupportedPairType {}; "stable pair")]
+#[test_case(PairType::Xyk {}, native_info("uusd"), Decimal::zero() => ContractError::AssetNotInPair {}; "asset not in pair")]
+#[test_case(PairType::Xyk {}, native_info("uosmo"), Decimal::one() => ContractError::InvalidSlippageTolerance {}; "invalid slippage")]
+fn test_plan_zap_in_errors(
+    pair_type: PairType,
+    offer: AssetInfo,
+    slippage_tolerance: Decimal,
+) -> ContractError {
+    let pool = pool(pair_type, 1_000, Decimal::zero());
+    let offer = Asset {
+        info: offer,
+        amount: Uint128::new(100),
+    };
+
+    plan_zap_in(&pool, &Addr::unchecked("dex"), &offer, slippage_tolerance).unwrap_err()
+}
+
+#[test]
+fn test_plan_zap_out_rejects_stable_pair() {
+    let pool = pool(PairType::Stable {}, 1_000, Decimal::zero());
+
+    let err = plan_zap_out(
+        &pool,
+        &Addr::unchecked("dex"),
+        Uint128::new(100),
+        &native_info("uosmo"),
+        Decimal::percent(1),
+    )
+    .unwrap_err();
+
+    assert_eq!(err, ContractError::UnsupportedPairType {});
+}
@@ -11,6 +11,7 @@ use cw_asset::AssetInfo;
 use std::convert::{TryFrom, TryInto};
 
 use crate::error::ContractError;
+pub use crate::key_codec::parse_u8_key;
 
 /// Authorization sanity
 pub fn only_allow_human_address(info: &MessageInfo, address: &Addr) -> Result<(), ContractError> {
@@ -33,7 +34,7 @@ pub fn only_allow_address(
     }
 }
 
-/// Authorization sanity
+/// Authorization sanity. For role based checks see [`crate::access_control::assert_role`].
 pub fn only_allow_addresses(
     api: &dyn Api,
     message_info: &MessageInfo,
@@ -44,10 +45,7 @@ pub fn only_allow_addresses(
             return Ok(Empty {});
         }
     }
-    Err(ContractError::Std(StdError::generic_err(format!(
-        "unauthorized - {}, required - {:?}",
-        message_info.sender, addresses
-    ))))
+    Err(ContractError::Unauthorized {})
 }
 
 const DECIMAL_FRACTIONAL: Uint128 = Uint128::new(1_000_000_000u128);
@@ -352,14 +350,6 @@ pub fn execute_send_tokens<D: CustomQuery, T>(
     Ok(Response::new().add_message(send))
 }
 
-/// 8u key helper
-pub fn parse_u8_key(data: &[u8]) -> Result<u8, ContractError> {
-    match data[0..8].try_into() {
-        Ok(bytes) => Ok(u8::from_be_bytes(bytes)),
-        Err(_) => Err(ContractError::CorruptedData {}),
-    }
-}
-
 /// Unwrap reply
 pub fn unwrap_reply(reply: &Reply) -> StdResult<SubMsgResponse> {
     reply
@@ -0,0 +1,314 @@
+use std::convert::TryFrom;
+
+use apollo_asset::{
+    asset::{Asset, AssetInfo},
+    pair::{PairInfo, PairType},
+};
+use cosmwasm_std::{Addr, CosmosMsg, Decimal, Isqrt, StdResult, Uint128, Uint512};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    dex_adaptor::{provide_liquidity_msgs, swap_msgs, withdraw_liquidity_msgs},
+    error::ContractError,
+};
+
+/// State of the pool a zap goes through. `reserves` follow the order of `pair.asset_infos`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ZapPool {
+    pub pair: PairInfo,
+    pub reserves: [Asset; 2],
+    pub lp_token: AssetInfo,
+    pub total_share: Uint128,
+    pub commission: Decimal,
+}
+
+/// One step of a zap, executed through the dex adaptor of the plan.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[schemars(deny_unknown_fields)]
+pub enum ZapAction {
+    Swap {
+        pair: Addr,
+        offer: Asset,
+        ask: AssetInfo,
+        minimum_receive: Uint128,
+    },
+    ProvideLiquidity {
+        pair: Addr,
+        assets: [Asset; 2],
+        min_lp_out: Uint128,
+    },
+    WithdrawLiquidity {
+        pair: Addr,
+        lp_token: Asset,
+        min_out: [Asset; 2],
+    },
+}
+
+impl ZapAction {
+    /// Messages executing the action through `dex_adaptor`, sending the output back.
+    pub fn into_msgs(self, dex_adaptor: &Addr) -> StdResult<Vec<CosmosMsg>> {
+        match self {
+            ZapAction::Swap {
+                pair,
+                offer,
+                ask,
+                minimum_receive,
+            } => swap_msgs(
+                dex_adaptor,
+                Some(pair.to_string()),
+                offer,
+                ask,
+                Some(minimum_receive),
+                None,
+            ),
+            ZapAction::ProvideLiquidity {
+                pair,
+                assets,
+                min_lp_out,
+            } => provide_liquidity_msgs(
+                dex_adaptor,
+                pair.to_string(),
+                assets.to_vec(),
+                Some(min_lp_out),
+                None,
+            ),
+            ZapAction::WithdrawLiquidity {
+                pair,
+                lp_token,
+                min_out,
+            } => withdraw_liquidity_msgs(
+                dex_adaptor,
+                pair.to_string(),
+                lp_token,
+                min_out.to_vec(),
+                None,
+            ),
+        }
+    }
+}
+
+/// Actions of a zap with the expected output (LP shares for a zap-in, the ask asset for a
+/// zap-out) and the minimum accepted once slippage is applied.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ZapPlan {
+    pub dex_adaptor: Addr,
+    pub actions: Vec<ZapAction>,
+    pub expected: Uint128,
+    pub minimum: Uint128,
+}
+
+impl ZapPlan {
+    pub fn into_msgs(self) -> StdResult<Vec<CosmosMsg>> {
+        let mut msgs = vec![];
+        for action in self.actions {
+            msgs.extend(action.into_msgs(&self.dex_adaptor)?);
+        }
+        Ok(msgs)
+    }
+}
+
+impl ZapPool {
+    fn validate(&self) -> Result<(), ContractError> {
+        if self.commission >= Decimal::one() {
+            return Err(ContractError::InvalidFee);
+        }
+        if self.total_share.is_zero() || self.reserves.iter().any(|r| r.amount.is_zero()) {
+            return Err(ContractError::InsufficientLiquidity {});
+        }
+        Ok(())
+    }
+
+    /// Index of `info` in the pool.
+    fn side(&self, info: &AssetInfo) -> Result<usize, ContractError> {
+        self.reserves
+            .iter()
+            .position(|r| r.info.equal(info))
+            .ok_or(ContractError::AssetNotInPair {})
+    }
+
+    /// Amount received when swapping `offer` from side `offer_side` at the given reserves.
+    fn simulate_swap(
+        &self,
+        offer_side: usize,
+        reserves: [Uint128; 2],
+        offer: Uint128,
+    ) -> Result<Uint128, ContractError> {
+        let (offer_pool, ask_pool) = (reserves[offer_side], reserves[1 - offer_side]);
+        let return_amount = match self.pair.pair_type {
+            PairType::Xyk {} => ask_pool.multiply_ratio(offer, offer_pool.checked_add(offer)?),
+            // stable pools need the stableswap invariant, pricing them 1:1 breaks the minimums
+            PairType::Stable {} | PairType::None {} => {
+                return Err(ContractError::UnsupportedPairType {})
+            }
+        };
+        Ok(return_amount - return_amount * self.commission)
+    }
+
+    /// Amount of `offer` to swap so that what is left and the swap output are in the pool
+    /// ratio after the swap.
+    fn zap_swap_amount(&self, offer_side: usize, offer: Uint128) -> Result<Uint128, ContractError> {
+        let offer_pool = self.reserves[offer_side].amount;
+        match self.pair.pair_type {
+            PairType::Xyk {} => optimal_swap_amount(offer_pool, offer, self.commission),
+            PairType::Stable {} | PairType::None {} => Err(ContractError::UnsupportedPairType {}),
+        }
+    }
+}
+
+fn apply_slippage(amount: Uint128, slippage_tolerance: Decimal) -> Uint128 {
+    amount * (Decimal::one() - slippage_tolerance)
+}
+
+/// Closed-form amount of `amount` to swap in a constant product pool holding `offer_pool`
+/// so that the remainder and the swap output can be provided without leftovers:
+///
+/// `s = (sqrt(((2 - f) * R)^2 + 4 * (1 - f) * A * R) - (2 - f) * R) / (2 * (1 - f))`
+pub fn optimal_swap_amount(
+    offer_pool: Uint128,
+    amount: Uint128,
+    commission: Decimal,
+) -> Result<Uint128, ContractError> {
+    if commission >= Decimal::one() {
+        return Err(ContractError::InvalidFee);
+    }
+    // everything is scaled by the decimal fractional so the fee stays exact
+    let unit = Uint512::from(Decimal::one().atomics());
+    let fee = Uint512::from(commission.atomics());
+    let reserve = Uint512::from(offer_pool);
+    let two_minus_fee = unit * Uint512::from(2u8) - fee;
+    let one_minus_fee = unit - fee;
+
+    let discriminant = (reserve * two_minus_fee) * (reserve * two_minus_fee)
+        + Uint512::from(4u8) * one_minus_fee * unit * Uint512::from(amount) * reserve;
+    let swap_amount =
+        (discriminant.isqrt() - two_minus_fee * reserve) / (Uint512::from(2u8) * one_minus_fee);
+
+    Ok(Uint128::try_from(swap_amount)?)
+}
+
+/// Plans swapping part of `offer` into the other pool asset and providing both as liquidity.
+pub fn plan_zap_in(
+    pool: &ZapPool,
+    dex_adaptor: &Addr,
+    offer: &Asset,
+    slippage_tolerance: Decimal,
+) -> Result<ZapPlan, ContractError> {
+    pool.validate()?;
+    if slippage_tolerance >= Decimal::one() {
+        return Err(ContractError::InvalidSlippageTolerance {});
+    }
+    let offer_side = pool.side(&offer.info)?;
+    let ask_side = 1 - offer_side;
+
+    let swap_amount = pool.zap_swap_amount(offer_side, offer.amount)?;
+    let mut reserves = [pool.reserves[0].amount, pool.reserves[1].amount];
+    let return_amount = pool.simulate_swap(offer_side, reserves, swap_amount)?;
+    reserves[offer_side] = reserves[offer_side].checked_add(swap_amount)?;
+    reserves[ask_side] = reserves[ask_side].checked_sub(return_amount)?;
+
+    let mut provided = [Uint128::zero(); 2];
+    provided[offer_side] = offer.amount - swap_amount;
+    provided[ask_side] = return_amount;
+    let lp_amount = std::cmp::min(
+        provided[0].multiply_ratio(pool.total_share, reserves[0]),
+        provided[1].multiply_ratio(pool.total_share, reserves[1]),
+    );
+    let min_lp_amount = apply_slippage(lp_amount, slippage_tolerance);
+
+    let mut actions = vec![];
+    if !swap_amount.is_zero() {
+        actions.push(ZapAction::Swap {
+            pair: pool.pair.contract_addr.clone(),
+            offer: offer.info.clone().to_asset(swap_amount),
+            ask: pool.reserves[ask_side].info.clone(),
+            minimum_receive: apply_slippage(return_amount, slippage_tolerance),
+        });
+    }
+    actions.push(ZapAction::ProvideLiquidity {
+        pair: pool.pair.contract_addr.clone(),
+        assets: [
+            pool.reserves[0].info.clone().to_asset(provided[0]),
+            pool.reserves[1].info.clone().to_asset(provided[1]),
+        ],
+        min_lp_out: min_lp_amount,
+    });
+
+    Ok(ZapPlan {
+        dex_adaptor: dex_adaptor.clone(),
+        actions,
+        expected: lp_amount,
+        minimum: min_lp_amount,
+    })
+}
+
+/// Plans withdrawing `lp_amount` of liquidity and swapping the other side into `ask`.
+pub fn plan_zap_out(
+    pool: &ZapPool,
+    dex_adaptor: &Addr,
+    lp_amount: Uint128,
+    ask: &AssetInfo,
+    slippage_tolerance: Decimal,
+) -> Result<ZapPlan, ContractError> {
+    pool.validate()?;
+    if slippage_tolerance >= Decimal::one() {
+        return Err(ContractError::InvalidSlippageTolerance {});
+    }
+    if lp_amount >= pool.total_share {
+        // the swap needs liquidity left in the pool
+        return Err(ContractError::InsufficientLiquidity {});
+    }
+    let ask_side = pool.side(ask)?;
+    let offer_side = 1 - ask_side;
+
+    let withdrawn = [
+        pool.reserves[0]
+            .amount
+            .multiply_ratio(lp_amount, pool.total_share),
+        pool.reserves[1]
+            .amount
+            .multiply_ratio(lp_amount, pool.total_share),
+    ];
+    let reserves = [
+        pool.reserves[0].amount - withdrawn[0],
+        pool.reserves[1].amount - withdrawn[1],
+    ];
+    let return_amount = pool.simulate_swap(offer_side, reserves, withdrawn[offer_side])?;
+    let expected = withdrawn[ask_side].checked_add(return_amount)?;
+
+    let mut actions = vec![ZapAction::WithdrawLiquidity {
+        pair: pool.pair.contract_addr.clone(),
+        lp_token: pool.lp_token.clone().to_asset(lp_amount),
+        min_out: [
+            pool.reserves[0]
+                .info
+                .clone()
+                .to_asset(apply_slippage(withdrawn[0], slippage_tolerance)),
+            pool.reserves[1]
+                .info
+                .clone()
+                .to_asset(apply_slippage(withdrawn[1], slippage_tolerance)),
+        ],
+    }];
+    if !withdrawn[offer_side].is_zero() {
+        actions.push(ZapAction::Swap {
+            pair: pool.pair.contract_addr.clone(),
+            offer: pool.reserves[offer_side]
+                .info
+                .clone()
+                .to_asset(withdrawn[offer_side]),
+            ask: ask.clone(),
+            minimum_receive: apply_slippage(return_amount, slippage_tolerance),
+        });
+    }
+
+    Ok(ZapPlan {
+        dex_adaptor: dex_adaptor.clone(),
+        actions,
+        expected,
+        minimum: apply_slippage(expected, slippage_tolerance),
+    })
+}